@@ -0,0 +1,293 @@
+/*
+ * Sanity guards on the inputs of an arbitrage evaluation
+ */
+
+use crate::config::{GuardAction, GuardConfig};
+use crate::models::{
+    ArbitrageSummary, GuardKind, GuardWarning, QuoteStatus, RecommendedAction, ReferencePrice,
+    Venue,
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use tracing::warn;
+
+/// Checks that the prices and pools an evaluation was built from are plausible, and
+/// rejects the detected arbitrage that relies on implausible ones.
+pub struct OpportunityGuards {
+    config: GuardConfig,
+}
+
+impl OpportunityGuards {
+    #[must_use]
+    pub fn new(config: GuardConfig) -> Self {
+        Self { config }
+    }
+
+    /// Whether pool liquidity has to be read for `check`.
+    #[must_use]
+    pub fn checks_pool_liquidity(&self) -> bool {
+        self.config.min_pool_liquidity_usd > Decimal::ZERO
+    }
+
+    /// Runs every enabled guard over the reference price, each DEX's effective price and
+    /// each pool's in-range liquidity in USDC.
+    #[must_use]
+    pub fn check(
+        &self,
+        reference: &ReferencePrice,
+        dex_prices: &[(Venue, Decimal)],
+        pool_liquidity_usd: &[(Venue, Decimal)],
+        now: DateTime<Utc>,
+    ) -> Vec<GuardWarning> {
+        let mut warnings = Vec::new();
+
+        let max_age_secs = self.config.max_cex_quote_age_secs;
+        if max_age_secs > 0 {
+            let oldest = reference
+                .quotes
+                .iter()
+                .filter(|quote| quote.status == QuoteStatus::Used)
+                .min_by_key(|quote| quote.quote.timestamp);
+            if let Some(oldest) = oldest {
+                let age_secs = (now - oldest.quote.timestamp).num_seconds();
+                if u64::try_from(age_secs).is_ok_and(|age| age > max_age_secs) {
+                    warnings.push(GuardWarning {
+                        guard: GuardKind::StaleCexQuote,
+                        venue: None,
+                        message: format!(
+                            "{} quote is {}s old, limit is {}s",
+                            oldest.quote.exchange, age_secs, max_age_secs
+                        ),
+                    });
+                }
+            }
+        }
+
+        if self.config.max_dex_deviation_percent > Decimal::ZERO && !reference.price.is_zero() {
+            for &(venue, price) in dex_prices {
+                let deviation_percent =
+                    ((price - reference.price) / reference.price * Decimal::ONE_HUNDRED).abs();
+                if deviation_percent > self.config.max_dex_deviation_percent {
+                    warnings.push(GuardWarning {
+                        guard: GuardKind::DexPriceDeviation,
+                        venue: Some(venue),
+                        message: format!(
+                            "Effective price {} is {}% from the CEX reference {}, limit is {}%",
+                            price.round_dp(2),
+                            deviation_percent.round_dp(2),
+                            reference.price.round_dp(2),
+                            self.config.max_dex_deviation_percent
+                        ),
+                    });
+                }
+            }
+        }
+
+        if self.checks_pool_liquidity() {
+            for &(venue, liquidity) in pool_liquidity_usd {
+                if liquidity < self.config.min_pool_liquidity_usd {
+                    warnings.push(GuardWarning {
+                        guard: GuardKind::LowPoolLiquidity,
+                        venue: Some(venue),
+                        message: format!(
+                            "In-range liquidity ${} is below the ${} floor",
+                            liquidity.round_dp(0),
+                            self.config.min_pool_liquidity_usd
+                        ),
+                    });
+                }
+            }
+        }
+
+        for warning in &warnings {
+            warn!(
+                "Guard {} tripped: {}",
+                warning.guard.as_str(),
+                warning.message
+            );
+        }
+        warnings
+    }
+
+    /// Under the reject action, turns detected arbitrage on any route touching a venue
+    /// that tripped a guard into `RejectedByGuard`.
+    pub fn apply(&self, warnings: &[GuardWarning], routes: &mut [ArbitrageSummary]) {
+        if self.config.action != GuardAction::Reject {
+            return;
+        }
+
+        for route in routes {
+            let affected = warnings.iter().any(|warning| {
+                warning
+                    .venue
+                    .is_none_or(|venue| venue == route.buy_venue || venue == route.sell_venue)
+            });
+            if affected && route.recommended_action == RecommendedAction::ArbitrageDetected {
+                route.recommended_action = RecommendedAction::RejectedByGuard;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{CexPrice, CexQuote};
+    use chrono::TimeDelta;
+
+    fn guards(action: GuardAction) -> OpportunityGuards {
+        OpportunityGuards::new(GuardConfig {
+            action,
+            max_cex_quote_age_secs: 30,
+            max_dex_deviation_percent: Decimal::ONE,
+            min_pool_liquidity_usd: Decimal::ZERO,
+        })
+    }
+
+    fn reference(now: DateTime<Utc>, age_secs: i64) -> ReferencePrice {
+        ReferencePrice {
+            pair: "ETH/USDC".to_string(),
+            price: Decimal::from(3000),
+            aggregation: None,
+            quotes: vec![CexQuote {
+                quote: CexPrice {
+                    exchange: "A".to_string(),
+                    pair: "ETH/USDC".to_string(),
+                    price: Decimal::from(3000),
+                    volume_24h: None,
+                    timestamp: now - TimeDelta::seconds(age_secs),
+                },
+                status: QuoteStatus::Used,
+            }],
+            spread_bps: Decimal::ZERO,
+        }
+    }
+
+    fn route(buy_venue: Venue, sell_venue: Venue) -> ArbitrageSummary {
+        ArbitrageSummary {
+            buy_venue,
+            sell_venue,
+            round_trip_usdc_in: Decimal::from(3000),
+            round_trip_usdc_out: Decimal::from(3010),
+            potential_profit_usd: Decimal::TEN,
+            total_gas_cost_usd: Decimal::ONE,
+            net_profit_usd: Decimal::from(9),
+            recommended_action: RecommendedAction::ArbitrageDetected,
+        }
+    }
+
+    fn kinds(warnings: &[GuardWarning]) -> Vec<(GuardKind, Option<Venue>)> {
+        warnings
+            .iter()
+            .map(|warning| (warning.guard, warning.venue))
+            .collect()
+    }
+
+    #[test]
+    fn quote_at_the_age_limit_is_not_stale() {
+        let now = Utc::now();
+        let guards = guards(GuardAction::Reject);
+
+        assert!(guards.check(&reference(now, 30), &[], &[], now).is_empty());
+        assert_eq!(
+            kinds(&guards.check(&reference(now, 31), &[], &[], now)),
+            [(GuardKind::StaleCexQuote, None)]
+        );
+    }
+
+    #[test]
+    fn flags_only_dex_prices_beyond_the_deviation_limit() {
+        let now = Utc::now();
+        let dex_prices = [
+            (Venue::UniswapV4, Decimal::from(3030)),
+            (Venue::Aerodrome, Decimal::from(3031)),
+        ];
+
+        let warnings = guards(GuardAction::Reject).check(&reference(now, 0), &dex_prices, &[], now);
+
+        assert_eq!(
+            kinds(&warnings),
+            [(GuardKind::DexPriceDeviation, Some(Venue::Aerodrome))]
+        );
+    }
+
+    #[test]
+    fn disabled_guards_never_trip() {
+        let now = Utc::now();
+        let guards = OpportunityGuards::new(GuardConfig {
+            action: GuardAction::Reject,
+            max_cex_quote_age_secs: 0,
+            max_dex_deviation_percent: Decimal::ZERO,
+            min_pool_liquidity_usd: Decimal::ZERO,
+        });
+
+        let warnings = guards.check(
+            &reference(now, 3600),
+            &[(Venue::UniswapV4, Decimal::from(6000))],
+            &[(Venue::Aerodrome, Decimal::ZERO)],
+            now,
+        );
+
+        assert!(warnings.is_empty());
+        assert!(!guards.checks_pool_liquidity());
+    }
+
+    #[test]
+    fn reject_only_downgrades_routes_touching_the_venue() {
+        let warnings = guards(GuardAction::Reject).check(
+            &reference(Utc::now(), 0),
+            &[(Venue::Aerodrome, Decimal::from(3100))],
+            &[],
+            Utc::now(),
+        );
+        let mut routes = [
+            route(Venue::UniswapV4, Venue::Aerodrome),
+            route(Venue::Cex, Venue::UniswapV4),
+        ];
+
+        guards(GuardAction::Reject).apply(&warnings, &mut routes);
+
+        assert_eq!(
+            routes[0].recommended_action,
+            RecommendedAction::RejectedByGuard
+        );
+        assert_eq!(
+            routes[1].recommended_action,
+            RecommendedAction::ArbitrageDetected
+        );
+    }
+
+    #[test]
+    fn flag_leaves_routes_untouched() {
+        let now = Utc::now();
+        let guards = guards(GuardAction::Flag);
+        let warnings = guards.check(&reference(now, 60), &[], &[], now);
+        let mut routes = [route(Venue::UniswapV4, Venue::Aerodrome)];
+
+        guards.apply(&warnings, &mut routes);
+
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            routes[0].recommended_action,
+            RecommendedAction::ArbitrageDetected
+        );
+    }
+
+    #[test]
+    fn venue_less_warning_rejects_every_route() {
+        let now = Utc::now();
+        let guards = guards(GuardAction::Reject);
+        let warnings = guards.check(&reference(now, 60), &[], &[], now);
+        let mut routes = [
+            route(Venue::UniswapV4, Venue::Aerodrome),
+            route(Venue::Aerodrome, Venue::Cex),
+            route(Venue::Cex, Venue::UniswapV4),
+        ];
+
+        guards.apply(&warnings, &mut routes);
+
+        assert!(routes
+            .iter()
+            .all(|route| route.recommended_action == RecommendedAction::RejectedByGuard));
+    }
+}
@@ -2,15 +2,68 @@
  * Analytics engine for arbitrage detection and calculation
  */
 
-use crate::dex::SwapQuote;
-use crate::models::{ArbitrageSummary, ArgusError, DexDetails, RecommendedAction, Result};
+pub mod guards;
+
+use crate::dex::{DexClient, SwapQuote};
+use crate::models::{
+    ArbitrageSummary, ArgusError, DexDetails, GasBreakdown, GasEstimateMethod, OrderBook,
+    OrderSide, RecommendedAction, Result, Venue,
+};
 use rust_decimal::Decimal;
 use std::str::FromStr;
+use tracing::debug;
 
 pub struct ArbitrageAnalyzer {
     eth_price_usd: Decimal,
 }
 
+/// Both legs of a USDC -> ETH -> USDC round trip across the two venues.
+#[derive(Debug, Clone)]
+pub struct RoundTripQuote {
+    pub buy_venue: Venue,
+    pub sell_venue: Venue,
+    pub usdc_in: Decimal,
+    pub eth_bought: Decimal,
+    pub usdc_out: Decimal,
+    pub buy_quote: SwapQuote,
+    pub sell_quote: SwapQuote,
+}
+
+impl RoundTripQuote {
+    /// Quote of the leg executed on `venue`.
+    #[must_use]
+    pub fn quote_for(&self, venue: Venue) -> &SwapQuote {
+        if venue == self.buy_venue {
+            &self.buy_quote
+        } else {
+            &self.sell_quote
+        }
+    }
+}
+
+/// USDC amounts of a USDC -> ETH -> USDC round trip between any two venues, net of CEX
+/// taker fees but not of gas.
+#[derive(Debug, Clone, Copy)]
+pub struct RouteQuote {
+    pub buy_venue: Venue,
+    pub sell_venue: Venue,
+    pub usdc_in: Decimal,
+    pub eth_bought: Decimal,
+    pub usdc_out: Decimal,
+}
+
+impl From<&RoundTripQuote> for RouteQuote {
+    fn from(round_trip: &RoundTripQuote) -> Self {
+        Self {
+            buy_venue: round_trip.buy_venue,
+            sell_venue: round_trip.sell_venue,
+            usdc_in: round_trip.usdc_in,
+            eth_bought: round_trip.eth_bought,
+            usdc_out: round_trip.usdc_out,
+        }
+    }
+}
+
 impl Default for ArbitrageAnalyzer {
     fn default() -> Self {
         Self::new()
@@ -29,37 +82,163 @@ impl ArbitrageAnalyzer {
         self.eth_price_usd = price;
     }
 
+    /// Quotes a round trip for `trade_size_eth`: buys ETH with USDC on the venue that
+    /// sells ETH cheaper, then sells the ETH actually received on the other one. Each
+    /// venue is read at its own pinned block.
+    pub async fn quote_round_trip(
+        uniswap: &dyn DexClient,
+        aerodrome: &dyn DexClient,
+        trade_size_eth: Decimal,
+        uniswap_block: u64,
+        aerodrome_block: u64,
+    ) -> Result<RoundTripQuote> {
+        let (uniswap_probe, aerodrome_probe) = tokio::try_join!(
+            uniswap.calculate_swap_output(trade_size_eth, true, uniswap_block),
+            aerodrome.calculate_swap_output(trade_size_eth, true, aerodrome_block)
+        )?;
+
+        let (buy_venue, buy_client, buy_probe, buy_block, sell_venue, sell_client, sell_block) =
+            if uniswap_probe.effective_price <= aerodrome_probe.effective_price {
+                (
+                    Venue::UniswapV4,
+                    uniswap,
+                    uniswap_probe,
+                    uniswap_block,
+                    Venue::Aerodrome,
+                    aerodrome,
+                    aerodrome_block,
+                )
+            } else {
+                (
+                    Venue::Aerodrome,
+                    aerodrome,
+                    aerodrome_probe,
+                    aerodrome_block,
+                    Venue::UniswapV4,
+                    uniswap,
+                    uniswap_block,
+                )
+            };
+
+        let usdc_in = (trade_size_eth * buy_probe.effective_price).round_dp(6);
+        let buy_quote = buy_client
+            .calculate_swap_output(usdc_in, false, buy_block)
+            .await?;
+        let eth_bought = buy_quote.amount_out;
+
+        let sell_quote = sell_client
+            .calculate_swap_output(eth_bought, true, sell_block)
+            .await?;
+        let usdc_out = sell_quote.amount_out;
+
+        Ok(RoundTripQuote {
+            buy_venue,
+            sell_venue,
+            usdc_in,
+            eth_bought,
+            usdc_out,
+            buy_quote,
+            sell_quote,
+        })
+    }
+
+    /// Quotes both routes between the CEX and `dex`, read at `block_number`: buying
+    /// `trade_size_eth` on the CEX to sell on the DEX, and buying on the DEX with the USDC
+    /// that size costs on the CEX to sell there. CEX legs fill against `order_book` and
+    /// pay `taker_fee_bps` in USDC. A route the fetched book is too thin for is skipped.
+    pub async fn quote_cex_routes(
+        dex: &dyn DexClient,
+        dex_venue: Venue,
+        order_book: &OrderBook,
+        taker_fee_bps: u32,
+        trade_size_eth: Decimal,
+        block_number: u64,
+    ) -> Result<Vec<RouteQuote>> {
+        let taker_fee = Decimal::from(taker_fee_bps) / Decimal::from(10_000);
+
+        let cex_buy = match order_book.vwap(OrderSide::Buy, trade_size_eth) {
+            Ok(fill) => fill,
+            Err(e) => {
+                debug!("Skipping CEX routes via {:?}: {}", dex_venue, e);
+                return Ok(Vec::new());
+            }
+        };
+        let usdc_in = (cex_buy.average_price * trade_size_eth).round_dp(6);
+
+        let (dex_sell, dex_buy) = tokio::try_join!(
+            dex.calculate_swap_output(trade_size_eth, true, block_number),
+            dex.calculate_swap_output(usdc_in, false, block_number)
+        )?;
+
+        let mut routes = vec![RouteQuote {
+            buy_venue: Venue::Cex,
+            sell_venue: dex_venue,
+            usdc_in: usdc_in * (Decimal::ONE + taker_fee),
+            eth_bought: trade_size_eth,
+            usdc_out: dex_sell.amount_out,
+        }];
+
+        match order_book.vwap(OrderSide::Sell, dex_buy.amount_out) {
+            Ok(cex_sell) => routes.push(RouteQuote {
+                buy_venue: dex_venue,
+                sell_venue: Venue::Cex,
+                usdc_in,
+                eth_bought: dex_buy.amount_out,
+                usdc_out: cex_sell.average_price * dex_buy.amount_out * (Decimal::ONE - taker_fee),
+            }),
+            Err(e) => debug!("Skipping {:?} -> CEX route: {}", dex_venue, e),
+        }
+
+        Ok(routes)
+    }
+
+    /// Nets gas out of the DEX round trip and every CEX route, returning one summary per
+    /// route with the most profitable first. A route pays the swap gas of each DEX leg it
+    /// has; CEX legs cost no gas.
     pub fn analyze_opportunity_with_gas(
         &self,
-        uniswap_quote: &SwapQuote,
-        aerodrome_quote: &SwapQuote,
-        trade_size_eth: Decimal,
-        _cex_price: Decimal,
+        round_trip: &RoundTripQuote,
+        cex_routes: &[RouteQuote],
         eth_gas_cost_usd: Decimal,
         base_gas_cost_usd: Decimal,
-    ) -> Result<ArbitrageSummary> {
-        let uniswap_price = uniswap_quote.effective_price;
-        let aerodrome_price = aerodrome_quote.effective_price;
+    ) -> Result<Vec<ArbitrageSummary>> {
+        let gas_cost_usd = |venue: Venue| match venue {
+            Venue::UniswapV4 => eth_gas_cost_usd,
+            Venue::Aerodrome => base_gas_cost_usd,
+            Venue::Cex => Decimal::ZERO,
+        };
 
-        let price_diff_per_eth = (uniswap_price - aerodrome_price).abs();
-        let potential_profit_usd = price_diff_per_eth * trade_size_eth;
+        let mut summaries: Vec<ArbitrageSummary> = std::iter::once(RouteQuote::from(round_trip))
+            .chain(cex_routes.iter().copied())
+            .map(|route| {
+                let potential_profit_usd = route.usdc_out - route.usdc_in;
 
-        let total_gas_cost_usd = eth_gas_cost_usd + base_gas_cost_usd;
+                let total_gas_cost_usd =
+                    gas_cost_usd(route.buy_venue) + gas_cost_usd(route.sell_venue);
 
-        let net_profit_usd = potential_profit_usd - total_gas_cost_usd;
+                let net_profit_usd = potential_profit_usd - total_gas_cost_usd;
 
-        let recommended_action = if net_profit_usd > Decimal::ZERO {
-            RecommendedAction::ArbitrageDetected
-        } else {
-            RecommendedAction::NoArbitrage
-        };
+                let recommended_action = if net_profit_usd > Decimal::ZERO {
+                    RecommendedAction::ArbitrageDetected
+                } else {
+                    RecommendedAction::NoArbitrage
+                };
 
-        Ok(ArbitrageSummary {
-            potential_profit_usd,
-            total_gas_cost_usd,
-            net_profit_usd,
-            recommended_action,
-        })
+                ArbitrageSummary {
+                    buy_venue: route.buy_venue,
+                    sell_venue: route.sell_venue,
+                    round_trip_usdc_in: route.usdc_in,
+                    round_trip_usdc_out: route.usdc_out,
+                    potential_profit_usd,
+                    total_gas_cost_usd,
+                    net_profit_usd,
+                    recommended_action,
+                }
+            })
+            .collect();
+
+        summaries.sort_by_key(|summary| std::cmp::Reverse(summary.net_profit_usd));
+        Ok(summaries)
     }
 
     pub fn wei_to_usd(&self, wei: u64) -> Result<Decimal> {
@@ -70,11 +249,19 @@ impl ArbitrageAnalyzer {
     }
 
     #[must_use]
-    pub fn create_dex_details(&self, quote: &SwapQuote, gas_cost_usd: Decimal) -> DexDetails {
+    pub fn create_dex_details(
+        &self,
+        quote: &SwapQuote,
+        gas_cost_usd: Decimal,
+        gas_estimate_method: GasEstimateMethod,
+        gas_breakdown: GasBreakdown,
+    ) -> DexDetails {
         DexDetails {
             effective_price_usd: quote.effective_price,
             price_impact_percent: quote.price_impact,
             estimated_gas_cost_usd: gas_cost_usd,
+            gas_estimate_method,
+            gas_breakdown,
         }
     }
 }
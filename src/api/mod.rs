@@ -3,7 +3,10 @@
  */
 
 use crate::config::Config;
-use crate::models::ArbitrageOpportunity;
+use crate::history::HistoryFilter;
+use crate::metrics::Metrics;
+use crate::models::{ArbitrageOpportunity, RecommendedAction, TradeSizeOptimization};
+use chrono::{DateTime, Utc};
 use rocket::serde::json::Json;
 use rocket::{get, routes, State};
 use rust_decimal::Decimal;
@@ -28,12 +31,7 @@ pub async fn get_arbitrage_opportunity(
                 format!("Invalid trade size: {e}"),
             )
         })?,
-        None => Decimal::from_str(&state.config.trading.default_trade_size_eth).map_err(|e| {
-            rocket::response::status::Custom(
-                rocket::http::Status::InternalServerError,
-                format!("Invalid default trade size: {e}"),
-            )
-        })?,
+        None => state.config.trading.default_trade_size_eth,
     };
 
     let service = state.arbitrage_service.read().await;
@@ -51,11 +49,119 @@ pub async fn get_arbitrage_opportunity(
     Ok(Json(opportunity))
 }
 
+#[get("/api/v1/optimal-trade-size?<min_trade_size_eth>&<max_trade_size_eth>&<samples>")]
+pub async fn get_optimal_trade_size(
+    min_trade_size_eth: Option<String>,
+    max_trade_size_eth: Option<String>,
+    samples: Option<usize>,
+    state: &State<ApiState>,
+) -> std::result::Result<Json<TradeSizeOptimization>, rocket::response::status::Custom<String>> {
+    let parse_size = |value: Option<String>, default: &str, name: &str| {
+        Decimal::from_str(value.as_deref().unwrap_or(default)).map_err(|e| {
+            rocket::response::status::Custom(
+                rocket::http::Status::BadRequest,
+                format!("Invalid {name}: {e}"),
+            )
+        })
+    };
+
+    let min_size = parse_size(min_trade_size_eth, "1", "min_trade_size_eth")?;
+    let max_size = parse_size(max_trade_size_eth, "500", "max_trade_size_eth")?;
+    let samples = samples.unwrap_or(10);
+
+    if min_size <= Decimal::ZERO || max_size <= min_size || !(3..=50).contains(&samples) {
+        return Err(rocket::response::status::Custom(
+            rocket::http::Status::BadRequest,
+            "Expected 0 < min_trade_size_eth < max_trade_size_eth and 3 <= samples <= 50"
+                .to_string(),
+        ));
+    }
+
+    let service = state.arbitrage_service.read().await;
+    let optimization = service
+        .find_optimal_trade_size(min_size, max_size, samples)
+        .await
+        .map_err(|e| {
+            tracing::error!("Error searching optimal trade size: {e:?}");
+            rocket::response::status::Custom(
+                rocket::http::Status::InternalServerError,
+                format!("Error searching optimal trade size: {e}"),
+            )
+        })?;
+
+    Ok(Json(optimization))
+}
+
+#[get("/api/v1/opportunities/history?<from>&<to>&<action>&<limit>")]
+pub async fn get_opportunity_history(
+    from: Option<String>,
+    to: Option<String>,
+    action: Option<String>,
+    limit: Option<usize>,
+    state: &State<ApiState>,
+) -> std::result::Result<Json<Vec<ArbitrageOpportunity>>, rocket::response::status::Custom<String>>
+{
+    let parse_time = |value: Option<String>, name: &str| {
+        value
+            .map(|v| {
+                DateTime::parse_from_rfc3339(&v)
+                    .map(|t| t.with_timezone(&Utc))
+                    .map_err(|e| {
+                        rocket::response::status::Custom(
+                            rocket::http::Status::BadRequest,
+                            format!("Invalid {name} timestamp: {e}"),
+                        )
+                    })
+            })
+            .transpose()
+    };
+
+    let filter = HistoryFilter {
+        from: parse_time(from, "from")?,
+        to: parse_time(to, "to")?,
+        action: action
+            .map(|a| RecommendedAction::from_str(&a))
+            .transpose()
+            .map_err(|e| {
+                rocket::response::status::Custom(rocket::http::Status::BadRequest, e.to_string())
+            })?,
+        limit,
+    };
+
+    let history = state.arbitrage_service.read().await.history();
+    Ok(Json(history.query(&filter)))
+}
+
+/// Effective configuration after all layers, with secrets redacted.
+#[get("/api/v1/config")]
+#[must_use]
+pub fn get_config(state: &State<ApiState>) -> Json<Config> {
+    Json(state.config.redacted())
+}
+
 #[must_use]
 pub fn create_rocket(state: ApiState) -> rocket::Rocket<rocket::Build> {
-    rocket::build()
-        .manage(state)
-        .mount("/", routes![get_arbitrage_opportunity, health_check])
+    let figment = rocket::Config::figment()
+        .merge(("address", state.config.server.host.clone()))
+        .merge(("port", state.config.server.port));
+
+    rocket::custom(figment).manage(state).mount(
+        "/",
+        routes![
+            get_arbitrage_opportunity,
+            get_optimal_trade_size,
+            get_opportunity_history,
+            get_config,
+            metrics,
+            health_check
+        ],
+    )
+}
+
+#[get("/metrics")]
+#[must_use]
+pub fn metrics() -> (rocket::http::ContentType, String) {
+    (rocket::http::ContentType::Plain, Metrics::global().render())
 }
 
 #[get("/health")]
@@ -0,0 +1,299 @@
+/*
+ * Composite CEX client combining several exchanges into one reference price
+ */
+
+use crate::cex::{symbols, CexClient};
+use crate::config::CexConfig;
+use crate::metrics::Metrics;
+use crate::models::{
+    ArgusError, CexPrice, CexQuote, OrderBook, PriceAggregation, QuoteStatus, ReferencePrice,
+    Result,
+};
+use async_trait::async_trait;
+use chrono::{TimeDelta, Utc};
+use rust_decimal::Decimal;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Queries every exchange concurrently and combines the fresh, mutually consistent quotes.
+/// Order books come from the first exchange, which is the one CEX legs trade on.
+pub struct AggregateCexClient {
+    clients: Vec<Box<dyn CexClient>>,
+    aggregation: PriceAggregation,
+    max_quote_age: Duration,
+    max_deviation_bps: u32,
+    min_quotes: usize,
+}
+
+impl AggregateCexClient {
+    #[must_use]
+    pub fn new(clients: Vec<Box<dyn CexClient>>, config: &CexConfig) -> Self {
+        Self {
+            clients,
+            aggregation: config.aggregation,
+            max_quote_age: Duration::from_secs(config.max_quote_age_secs),
+            max_deviation_bps: config.max_deviation_bps,
+            min_quotes: config.min_quotes.max(1),
+        }
+    }
+
+    /// Spot prices of every exchange that answered; failures are logged and skipped.
+    async fn fetch_quotes(&self, base: &str, quote: &str) -> Vec<CexPrice> {
+        let results = futures::future::join_all(self.clients.iter().map(|client| async move {
+            client
+                .get_spot_price(base, quote)
+                .await
+                .map_err(|e| {
+                    Metrics::global().record_error(&e);
+                    warn!("Dropping {} quote from aggregate: {}", client.name(), e);
+                })
+                .ok()
+        }))
+        .await;
+
+        results.into_iter().flatten().collect()
+    }
+
+    /// Rejects quotes without a positive price and marks quotes older than the maximum
+    /// age as stale, then quotes too far from the median of the remaining ones as outliers.
+    fn classify(&self, quotes: Vec<CexPrice>) -> Vec<CexQuote> {
+        let now = Utc::now();
+        let max_age = TimeDelta::from_std(self.max_quote_age).unwrap_or(TimeDelta::MAX);
+
+        let mut quotes: Vec<CexQuote> = quotes
+            .into_iter()
+            .map(|quote| {
+                let status = if quote.price <= Decimal::ZERO {
+                    QuoteStatus::Rejected
+                } else if now.signed_duration_since(quote.timestamp) > max_age {
+                    QuoteStatus::Stale
+                } else {
+                    QuoteStatus::Used
+                };
+                CexQuote { quote, status }
+            })
+            .collect();
+
+        let fresh: Vec<Decimal> = used_prices(&quotes).collect();
+        let Some(median) = median(fresh) else {
+            return quotes;
+        };
+
+        let max_deviation = Decimal::from(self.max_deviation_bps);
+        for quote in &mut quotes {
+            if quote.status == QuoteStatus::Used
+                && deviation_bps(quote.quote.price, median) > max_deviation
+            {
+                quote.status = QuoteStatus::Outlier;
+            }
+        }
+
+        quotes
+    }
+}
+
+fn used_prices(quotes: &[CexQuote]) -> impl Iterator<Item = Decimal> + '_ {
+    quotes
+        .iter()
+        .filter(|quote| quote.status == QuoteStatus::Used)
+        .map(|quote| quote.quote.price)
+}
+
+fn median(mut prices: Vec<Decimal>) -> Option<Decimal> {
+    prices.sort();
+    let mid = prices.len() / 2;
+    match prices.len() {
+        0 => None,
+        len if len % 2 == 1 => Some(prices[mid]),
+        _ => Some((prices[mid - 1] + prices[mid]) / Decimal::TWO),
+    }
+}
+
+/// Average of the used quotes weighted by 24 hour volume, over the quotes that report
+/// a volume. `None` when none do.
+fn volume_weighted(quotes: &[CexQuote]) -> Option<Decimal> {
+    let (notional, volume) = quotes
+        .iter()
+        .filter(|quote| quote.status == QuoteStatus::Used)
+        .filter_map(|quote| {
+            quote
+                .quote
+                .volume_24h
+                .map(|volume| (quote.quote.price, volume))
+        })
+        .fold(
+            (Decimal::ZERO, Decimal::ZERO),
+            |(notional, total), (price, volume)| (notional + price * volume, total + volume),
+        );
+
+    (volume > Decimal::ZERO).then(|| notional / volume)
+}
+
+/// Distance of `price` from `reference` in basis points; unbounded for a zero reference.
+fn deviation_bps(price: Decimal, reference: Decimal) -> Decimal {
+    (price - reference)
+        .abs()
+        .checked_div(reference)
+        .map_or(Decimal::MAX, |ratio| ratio * Decimal::from(10_000))
+}
+
+#[async_trait]
+impl CexClient for AggregateCexClient {
+    fn name(&self) -> &'static str {
+        "Aggregate"
+    }
+
+    async fn get_spot_price(&self, base: &str, quote: &str) -> Result<CexPrice> {
+        let reference = self.get_reference_price(base, quote).await?;
+        let used: Vec<&CexPrice> = reference
+            .quotes
+            .iter()
+            .filter(|quote| quote.status == QuoteStatus::Used)
+            .map(|quote| &quote.quote)
+            .collect();
+
+        Ok(CexPrice {
+            exchange: self.name().to_string(),
+            pair: reference.pair,
+            price: reference.price,
+            volume_24h: used.iter().map(|quote| quote.volume_24h).sum(),
+            timestamp: used
+                .iter()
+                .map(|quote| quote.timestamp)
+                .min()
+                .unwrap_or_else(Utc::now),
+        })
+    }
+
+    async fn get_reference_price(&self, base: &str, quote: &str) -> Result<ReferencePrice> {
+        let pair = symbols::display_pair(base, quote);
+        let quotes = self.classify(self.fetch_quotes(base, quote).await);
+
+        let used: Vec<Decimal> = used_prices(&quotes).collect();
+        if used.len() < self.min_quotes {
+            return Err(ArgusError::CexApiError(format!(
+                "Only {} of {} exchange quotes for {pair} are fresh and consistent, {} required",
+                used.len(),
+                self.clients.len(),
+                self.min_quotes
+            )));
+        }
+
+        let (Some(low), Some(high)) = (used.iter().min().copied(), used.iter().max().copied())
+        else {
+            return Err(ArgusError::CexApiError(format!(
+                "No exchange quote for {pair}"
+            )));
+        };
+
+        let median = median(used).unwrap_or(low);
+        let price = match self.aggregation {
+            PriceAggregation::Median => median,
+            PriceAggregation::VolumeWeighted => volume_weighted(&quotes).unwrap_or_else(|| {
+                debug!("No exchange reported volume for {pair}, using the median");
+                median
+            }),
+        };
+
+        let spread_bps = (high - low)
+            .checked_div(price)
+            .ok_or_else(|| ArgusError::CexApiError(format!("Aggregate {pair} price is zero")))?
+            * Decimal::from(10_000);
+
+        Ok(ReferencePrice {
+            pair,
+            price,
+            aggregation: Some(self.aggregation),
+            spread_bps,
+            quotes,
+        })
+    }
+
+    async fn get_order_book(&self, base: &str, quote: &str, depth: usize) -> Result<OrderBook> {
+        let primary = self
+            .clients
+            .first()
+            .ok_or_else(|| ArgusError::ConfigError("No CEX configured".to_string()))?;
+        primary.get_order_book(base, quote, depth).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client() -> AggregateCexClient {
+        AggregateCexClient {
+            clients: Vec::new(),
+            aggregation: PriceAggregation::Median,
+            max_quote_age: Duration::from_secs(30),
+            max_deviation_bps: 50,
+            min_quotes: 1,
+        }
+    }
+
+    fn price(exchange: &str, price: i64, age_secs: i64) -> CexPrice {
+        CexPrice {
+            exchange: exchange.to_string(),
+            pair: "ETH/USDC".to_string(),
+            price: Decimal::from(price),
+            volume_24h: None,
+            timestamp: Utc::now() - TimeDelta::seconds(age_secs),
+        }
+    }
+
+    fn statuses(quotes: &[CexQuote]) -> Vec<QuoteStatus> {
+        quotes.iter().map(|quote| quote.status).collect()
+    }
+
+    #[test]
+    fn classifies_stale_and_outlying_quotes() {
+        let quotes = client().classify(vec![
+            price("A", 3000, 0),
+            price("B", 3001, 0),
+            price("C", 3100, 0),
+            price("D", 3000, 120),
+        ]);
+        assert_eq!(
+            statuses(&quotes),
+            [
+                QuoteStatus::Used,
+                QuoteStatus::Used,
+                QuoteStatus::Outlier,
+                QuoteStatus::Stale
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_non_positive_quotes() {
+        let quotes = client().classify(vec![
+            price("A", 0, 0),
+            price("B", 3000, 0),
+            price("C", -1, 0),
+        ]);
+        assert_eq!(
+            statuses(&quotes),
+            [
+                QuoteStatus::Rejected,
+                QuoteStatus::Used,
+                QuoteStatus::Rejected
+            ]
+        );
+
+        let quotes = client().classify(vec![price("A", 0, 0)]);
+        assert_eq!(statuses(&quotes), [QuoteStatus::Rejected]);
+    }
+
+    #[test]
+    fn deviation_from_zero_reference_is_unbounded() {
+        assert_eq!(
+            deviation_bps(Decimal::from(3000), Decimal::ZERO),
+            Decimal::MAX
+        );
+        assert_eq!(
+            deviation_bps(Decimal::from(3003), Decimal::from(3000)),
+            Decimal::from(10)
+        );
+    }
+}
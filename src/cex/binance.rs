@@ -2,22 +2,37 @@
  * Binance CEX client implementation
  */
 
-use crate::cex::CexClient;
-use crate::models::{ArgusError, CexPrice, Result};
+use crate::cex::stream::{json_decimal, TickerFeed, TickerUpdate};
+use crate::cex::{http_client, parse_levels, symbols, CexClient};
+use crate::models::{ArgusError, CexPrice, OrderBook, Result};
+use crate::retry::RetryPolicy;
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use reqwest::Client;
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use serde_json::Value;
 use std::str::FromStr;
 
 pub struct BinanceClient {
     client: Client,
+    retry: RetryPolicy,
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct BinanceTickerResponse {
-    price: String,
+    last_price: String,
+    /// Base asset volume of the rolling 24 hour window
+    volume: String,
+    /// End of the window in milliseconds, which is the time of the last update
+    close_time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceDepthResponse {
+    bids: Vec<(String, String)>,
+    asks: Vec<(String, String)>,
 }
 
 impl Default for BinanceClient {
@@ -29,41 +44,138 @@ impl Default for BinanceClient {
 impl BinanceClient {
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-        }
+        Self::with_retry_policy(RetryPolicy::default())
     }
 
-    fn format_symbol(base: &str, quote: &str) -> String {
-        format!("{}{}", base.to_uppercase(), quote.to_uppercase())
+    #[must_use]
+    pub fn with_retry_policy(retry: RetryPolicy) -> Self {
+        Self {
+            client: http_client(&retry),
+            retry,
+        }
     }
 }
 
 #[async_trait]
 impl CexClient for BinanceClient {
+    fn name(&self) -> &'static str {
+        "Binance"
+    }
+
     async fn get_spot_price(&self, base: &str, quote: &str) -> Result<CexPrice> {
-        let symbol = Self::format_symbol(base, quote);
-        let url = format!("https://api.binance.com/api/v3/ticker/price?symbol={symbol}");
+        let symbol = symbols::BINANCE.symbol(base, quote);
+        let url = format!("https://api.binance.com/api/v3/ticker/24hr?symbol={symbol}");
 
         let response = self
-            .client
-            .get(&url)
-            .send()
-            .await?
-            .json::<BinanceTickerResponse>()
-            .await
-            .map_err(|e| {
-                ArgusError::CexApiError(format!("Failed to parse Binance response: {e}"))
-            })?;
-
-        let price = Decimal::from_str(&response.price)
+            .retry
+            .retry(self.name(), "ticker", || async {
+                self.client
+                    .get(&url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<BinanceTickerResponse>()
+                    .await
+                    .map_err(|e| {
+                        ArgusError::CexApiError(format!("Failed to parse Binance response: {e}"))
+                    })
+            })
+            .await?;
+
+        let price = Decimal::from_str(&response.last_price)
             .map_err(|e| ArgusError::CexApiError(format!("Failed to parse price: {e}")))?;
+        let volume = Decimal::from_str(&response.volume)
+            .map_err(|e| ArgusError::CexApiError(format!("Failed to parse volume: {e}")))?;
 
         Ok(CexPrice {
-            exchange: "Binance".to_string(),
-            pair: format!("{}/{}", base.to_uppercase(), quote.to_uppercase()),
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
             price,
+            volume_24h: Some(volume),
+            timestamp: DateTime::from_timestamp_millis(response.close_time)
+                .unwrap_or_else(Utc::now),
+        })
+    }
+
+    async fn get_order_book(&self, base: &str, quote: &str, depth: usize) -> Result<OrderBook> {
+        let symbol = symbols::BINANCE.symbol(base, quote);
+        // Binance serves at most 5000 levels per side
+        let limit = depth.clamp(1, 5000);
+        let url = format!("https://api.binance.com/api/v3/depth?symbol={symbol}&limit={limit}");
+
+        let response = self
+            .retry
+            .retry(self.name(), "order_book", || async {
+                self.client
+                    .get(&url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<BinanceDepthResponse>()
+                    .await
+                    .map_err(|e| {
+                        ArgusError::CexApiError(format!(
+                            "Failed to parse Binance depth response: {e}"
+                        ))
+                    })
+            })
+            .await?;
+
+        let levels = |rows: &[(String, String)], descending| {
+            parse_levels(
+                self.name(),
+                rows.iter()
+                    .map(|(price, qty)| (price.as_str(), qty.as_str())),
+                descending,
+                depth,
+            )
+        };
+
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
+            bids: levels(&response.bids, true)?,
+            asks: levels(&response.asks, false)?,
             timestamp: Utc::now(),
         })
     }
 }
+
+/// Rolling 24 hour ticker stream, pushed every second.
+pub struct BinanceTickerFeed {
+    symbol: String,
+}
+
+impl BinanceTickerFeed {
+    #[must_use]
+    pub fn new(base: &str, quote: &str) -> Self {
+        Self {
+            symbol: symbols::BINANCE.symbol(base, quote).to_lowercase(),
+        }
+    }
+}
+
+impl TickerFeed for BinanceTickerFeed {
+    fn url(&self) -> String {
+        format!("wss://stream.binance.com:9443/ws/{}@ticker", self.symbol)
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn parse(&self, message: &str) -> Result<Option<TickerUpdate>> {
+        let message: Value = serde_json::from_str(message)?;
+        if message["e"] != "24hrTicker" {
+            return Ok(None);
+        }
+
+        Ok(Some(TickerUpdate {
+            price: json_decimal(&message["c"]),
+            volume_24h: json_decimal(&message["v"]),
+            timestamp: message["E"]
+                .as_i64()
+                .and_then(DateTime::from_timestamp_millis),
+        }))
+    }
+}
@@ -0,0 +1,147 @@
+/*
+ * Bitstamp CEX client implementation
+ */
+
+use crate::cex::{http_client, parse_levels, symbols, CexClient};
+use crate::models::{ArgusError, CexPrice, OrderBook, Result};
+use crate::retry::RetryPolicy;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::str::FromStr;
+
+pub struct BitstampClient {
+    client: Client,
+    retry: RetryPolicy,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitstampTickerResponse {
+    last: String,
+    /// Base asset volume of the last 24 hours
+    volume: String,
+    /// Seconds, as a string
+    timestamp: String,
+}
+
+/// Levels are `[price, amount]`
+#[derive(Debug, Deserialize)]
+struct BitstampBookResponse {
+    bids: Vec<(String, String)>,
+    asks: Vec<(String, String)>,
+}
+
+impl Default for BitstampClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitstampClient {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_retry_policy(RetryPolicy::default())
+    }
+
+    #[must_use]
+    pub fn with_retry_policy(retry: RetryPolicy) -> Self {
+        Self {
+            client: http_client(&retry),
+            retry,
+        }
+    }
+}
+
+#[async_trait]
+impl CexClient for BitstampClient {
+    fn name(&self) -> &'static str {
+        "Bitstamp"
+    }
+
+    async fn get_spot_price(&self, base: &str, quote: &str) -> Result<CexPrice> {
+        let url = format!(
+            "https://www.bitstamp.net/api/v2/ticker/{}/",
+            symbols::BITSTAMP.symbol(base, quote)
+        );
+
+        let response = self
+            .retry
+            .retry(self.name(), "ticker", || async {
+                self.client
+                    .get(&url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<BitstampTickerResponse>()
+                    .await
+                    .map_err(|e| {
+                        ArgusError::CexApiError(format!("Failed to parse Bitstamp response: {e}"))
+                    })
+            })
+            .await?;
+
+        let price = Decimal::from_str(&response.last)
+            .map_err(|e| ArgusError::CexApiError(format!("Failed to parse price: {e}")))?;
+        let volume = Decimal::from_str(&response.volume)
+            .map_err(|e| ArgusError::CexApiError(format!("Failed to parse volume: {e}")))?;
+
+        Ok(CexPrice {
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
+            price,
+            volume_24h: Some(volume),
+            timestamp: response
+                .timestamp
+                .parse()
+                .ok()
+                .and_then(|secs| DateTime::from_timestamp(secs, 0))
+                .unwrap_or_else(Utc::now),
+        })
+    }
+
+    async fn get_order_book(&self, base: &str, quote: &str, depth: usize) -> Result<OrderBook> {
+        // The whole book is returned and trimmed to `depth` once parsed
+        let url = format!(
+            "https://www.bitstamp.net/api/v2/order_book/{}/",
+            symbols::BITSTAMP.symbol(base, quote)
+        );
+
+        let response = self
+            .retry
+            .retry(self.name(), "order_book", || async {
+                self.client
+                    .get(&url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<BitstampBookResponse>()
+                    .await
+                    .map_err(|e| {
+                        ArgusError::CexApiError(format!(
+                            "Failed to parse Bitstamp order book response: {e}"
+                        ))
+                    })
+            })
+            .await?;
+
+        let levels = |rows: &[(String, String)], descending| {
+            parse_levels(
+                self.name(),
+                rows.iter()
+                    .map(|(price, amount)| (price.as_str(), amount.as_str())),
+                descending,
+                depth,
+            )
+        };
+
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
+            bids: levels(&response.bids, true)?,
+            asks: levels(&response.asks, false)?,
+            timestamp: Utc::now(),
+        })
+    }
+}
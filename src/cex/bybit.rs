@@ -0,0 +1,167 @@
+/*
+ * Bybit CEX client implementation
+ */
+
+use crate::cex::{http_client, parse_levels, symbols, CexClient};
+use crate::models::{ArgusError, CexPrice, OrderBook, Result};
+use crate::retry::RetryPolicy;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::str::FromStr;
+
+pub struct BybitClient {
+    client: Client,
+    retry: RetryPolicy,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitResponse<T> {
+    ret_code: i64,
+    ret_msg: String,
+    result: Option<T>,
+    /// Server time in milliseconds
+    time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitTickers {
+    list: Vec<BybitTicker>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitTicker {
+    last_price: String,
+    /// Base asset volume of the last 24 hours
+    volume24h: String,
+}
+
+/// Levels are `[price, size]`
+#[derive(Debug, Deserialize)]
+struct BybitBook {
+    b: Vec<(String, String)>,
+    a: Vec<(String, String)>,
+}
+
+impl Default for BybitClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BybitClient {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_retry_policy(RetryPolicy::default())
+    }
+
+    #[must_use]
+    pub fn with_retry_policy(retry: RetryPolicy) -> Self {
+        Self {
+            client: http_client(&retry),
+            retry,
+        }
+    }
+
+    /// `result` of a successful response and the server time it was produced at.
+    async fn get_result<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        operation: &str,
+    ) -> Result<(T, DateTime<Utc>)> {
+        let response = self
+            .retry
+            .retry(self.name(), operation, || async {
+                self.client
+                    .get(url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<BybitResponse<T>>()
+                    .await
+                    .map_err(|e| {
+                        ArgusError::CexApiError(format!("Failed to parse Bybit response: {e}"))
+                    })
+            })
+            .await?;
+
+        if response.ret_code != 0 {
+            return Err(ArgusError::CexApiError(format!(
+                "Bybit API error {}: {}",
+                response.ret_code, response.ret_msg
+            )));
+        }
+
+        let result = response
+            .result
+            .ok_or_else(|| ArgusError::CexApiError(format!("No {operation} data found")))?;
+        let time = DateTime::from_timestamp_millis(response.time).unwrap_or_else(Utc::now);
+        Ok((result, time))
+    }
+}
+
+#[async_trait]
+impl CexClient for BybitClient {
+    fn name(&self) -> &'static str {
+        "Bybit"
+    }
+
+    async fn get_spot_price(&self, base: &str, quote: &str) -> Result<CexPrice> {
+        let url = format!(
+            "https://api.bybit.com/v5/market/tickers?category=spot&symbol={}",
+            symbols::BYBIT.symbol(base, quote)
+        );
+        let (tickers, time): (BybitTickers, _) = self.get_result(&url, "ticker").await?;
+
+        let ticker = tickers
+            .list
+            .first()
+            .ok_or_else(|| ArgusError::CexApiError("No ticker data found".to_string()))?;
+
+        let price = Decimal::from_str(&ticker.last_price)
+            .map_err(|e| ArgusError::CexApiError(format!("Failed to parse price: {e}")))?;
+        let volume = Decimal::from_str(&ticker.volume24h)
+            .map_err(|e| ArgusError::CexApiError(format!("Failed to parse volume: {e}")))?;
+
+        Ok(CexPrice {
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
+            price,
+            volume_24h: Some(volume),
+            timestamp: time,
+        })
+    }
+
+    async fn get_order_book(&self, base: &str, quote: &str, depth: usize) -> Result<OrderBook> {
+        // Bybit serves at most 200 spot levels per side
+        let url = format!(
+            "https://api.bybit.com/v5/market/orderbook?category=spot&symbol={}&limit={}",
+            symbols::BYBIT.symbol(base, quote),
+            depth.clamp(1, 200)
+        );
+        let (book, _): (BybitBook, _) = self.get_result(&url, "order_book").await?;
+
+        let levels = |rows: &[(String, String)], descending| {
+            parse_levels(
+                self.name(),
+                rows.iter()
+                    .map(|(price, size)| (price.as_str(), size.as_str())),
+                descending,
+                depth,
+            )
+        };
+
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
+            bids: levels(&book.b, true)?,
+            asks: levels(&book.a, false)?,
+            timestamp: Utc::now(),
+        })
+    }
+}
@@ -2,16 +2,37 @@
  * Coinbase CEX client implementation
  */
 
-use crate::cex::CexClient;
-use crate::models::{ArgusError, CexPrice, Result};
+use crate::cex::stream::{json_decimal, TickerFeed, TickerUpdate};
+use crate::cex::{http_client, parse_levels, symbols, CexClient};
+use crate::models::{ArgusError, CexPrice, OrderBook, Result};
+use crate::retry::RetryPolicy;
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use reqwest::Client;
 use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::Value;
 use std::str::FromStr;
 
 pub struct CoinbaseClient {
     client: Client,
+    retry: RetryPolicy,
+}
+
+/// Last trade of a product
+#[derive(Debug, Deserialize)]
+struct CoinbaseTickerResponse {
+    price: String,
+    /// Base asset volume of the last 24 hours
+    volume: String,
+    time: DateTime<Utc>,
+}
+
+/// Level 2 levels are `[price, size, num_orders]`
+#[derive(Debug, Deserialize)]
+struct CoinbaseBookResponse {
+    bids: Vec<(String, String, u64)>,
+    asks: Vec<(String, String, u64)>,
 }
 
 impl Default for CoinbaseClient {
@@ -23,43 +44,149 @@ impl Default for CoinbaseClient {
 impl CoinbaseClient {
     #[must_use]
     pub fn new() -> Self {
+        Self::with_retry_policy(RetryPolicy::default())
+    }
+
+    #[must_use]
+    pub fn with_retry_policy(retry: RetryPolicy) -> Self {
         Self {
-            client: Client::new(),
+            client: http_client(&retry),
+            retry,
         }
     }
 }
 
 #[async_trait]
 impl CexClient for CoinbaseClient {
+    fn name(&self) -> &'static str {
+        "Coinbase"
+    }
+
     async fn get_spot_price(&self, base: &str, quote: &str) -> Result<CexPrice> {
         let url = format!(
-            "https://api.coinbase.com/v2/exchange-rates?currency={}&rates={}",
-            base.to_uppercase(),
-            quote.to_uppercase()
+            "https://api.exchange.coinbase.com/products/{}/ticker",
+            symbols::COINBASE.symbol(base, quote)
         );
 
         let response = self
-            .client
-            .get(&url)
-            .send()
-            .await?
-            .json::<serde_json::Value>()
+            .retry
+            .retry(self.name(), "ticker", || async {
+                self.client
+                    .get(&url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<CoinbaseTickerResponse>()
+                    .await
+                    .map_err(|e| {
+                        ArgusError::CexApiError(format!("Failed to parse Coinbase response: {e}"))
+                    })
+            })
             .await?;
 
-        let rate_str = response["data"]["rates"][quote.to_uppercase()]
-            .as_str()
-            .ok_or_else(|| {
-                ArgusError::CexApiError("Failed to parse Coinbase response".to_string())
-            })?;
-
-        let price = Decimal::from_str(rate_str)
+        let price = Decimal::from_str(&response.price)
             .map_err(|e| ArgusError::CexApiError(format!("Failed to parse price: {e}")))?;
+        let volume = Decimal::from_str(&response.volume)
+            .map_err(|e| ArgusError::CexApiError(format!("Failed to parse volume: {e}")))?;
 
         Ok(CexPrice {
-            exchange: "Coinbase".to_string(),
-            pair: format!("{}/{}", base.to_uppercase(), quote.to_uppercase()),
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
             price,
+            volume_24h: Some(volume),
+            timestamp: response.time,
+        })
+    }
+
+    async fn get_order_book(&self, base: &str, quote: &str, depth: usize) -> Result<OrderBook> {
+        // Level 2 is the full aggregated book, trimmed to `depth` once parsed
+        let url = format!(
+            "https://api.exchange.coinbase.com/products/{}/book?level=2",
+            symbols::COINBASE.symbol(base, quote)
+        );
+
+        let response = self
+            .retry
+            .retry(self.name(), "order_book", || async {
+                self.client
+                    .get(&url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<CoinbaseBookResponse>()
+                    .await
+                    .map_err(|e| {
+                        ArgusError::CexApiError(format!(
+                            "Failed to parse Coinbase book response: {e}"
+                        ))
+                    })
+            })
+            .await?;
+
+        let levels = |rows: &[(String, String, u64)], descending| {
+            parse_levels(
+                self.name(),
+                rows.iter()
+                    .map(|(price, size, _)| (price.as_str(), size.as_str())),
+                descending,
+                depth,
+            )
+        };
+
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
+            bids: levels(&response.bids, true)?,
+            asks: levels(&response.asks, false)?,
             timestamp: Utc::now(),
         })
     }
 }
+
+/// Ticker channel, pushed on every trade, with heartbeats keeping quiet connections alive.
+pub struct CoinbaseTickerFeed {
+    product: String,
+}
+
+impl CoinbaseTickerFeed {
+    #[must_use]
+    pub fn new(base: &str, quote: &str) -> Self {
+        Self {
+            product: symbols::COINBASE.symbol(base, quote),
+        }
+    }
+}
+
+impl TickerFeed for CoinbaseTickerFeed {
+    fn url(&self) -> String {
+        "wss://ws-feed.exchange.coinbase.com".to_string()
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        vec![serde_json::json!({
+            "type": "subscribe",
+            "product_ids": [self.product],
+            "channels": ["ticker", "heartbeat"],
+        })
+        .to_string()]
+    }
+
+    fn parse(&self, message: &str) -> Result<Option<TickerUpdate>> {
+        let message: Value = serde_json::from_str(message)?;
+        match message["type"].as_str() {
+            Some("ticker") => Ok(Some(TickerUpdate {
+                price: json_decimal(&message["price"]),
+                volume_24h: json_decimal(&message["volume_24h"]),
+                timestamp: message["time"]
+                    .as_str()
+                    .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+                    .map(|time| time.with_timezone(&Utc)),
+            })),
+            Some("error") => Err(ArgusError::CexApiError(format!(
+                "Coinbase stream error: {}",
+                message["message"]
+            ))),
+            _ => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,155 @@
+/*
+ * Gemini CEX client implementation
+ */
+
+use crate::cex::{http_client, parse_levels, symbols, CexClient};
+use crate::models::{ArgusError, CexPrice, OrderBook, Result};
+use crate::retry::RetryPolicy;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+pub struct GeminiClient {
+    client: Client,
+    retry: RetryPolicy,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiTickerResponse {
+    last: String,
+    /// 24 hour volume keyed by asset code, plus a `timestamp` in milliseconds
+    volume: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiBookResponse {
+    bids: Vec<GeminiLevel>,
+    asks: Vec<GeminiLevel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiLevel {
+    price: String,
+    amount: String,
+}
+
+impl Default for GeminiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GeminiClient {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_retry_policy(RetryPolicy::default())
+    }
+
+    #[must_use]
+    pub fn with_retry_policy(retry: RetryPolicy) -> Self {
+        Self {
+            client: http_client(&retry),
+            retry,
+        }
+    }
+}
+
+#[async_trait]
+impl CexClient for GeminiClient {
+    fn name(&self) -> &'static str {
+        "Gemini"
+    }
+
+    async fn get_spot_price(&self, base: &str, quote: &str) -> Result<CexPrice> {
+        let url = format!(
+            "https://api.gemini.com/v1/pubticker/{}",
+            symbols::GEMINI.symbol(base, quote)
+        );
+
+        let response = self
+            .retry
+            .retry(self.name(), "ticker", || async {
+                self.client
+                    .get(&url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<GeminiTickerResponse>()
+                    .await
+                    .map_err(|e| {
+                        ArgusError::CexApiError(format!("Failed to parse Gemini response: {e}"))
+                    })
+            })
+            .await?;
+
+        let price = Decimal::from_str(&response.last)
+            .map_err(|e| ArgusError::CexApiError(format!("Failed to parse price: {e}")))?;
+        let volume = response
+            .volume
+            .get(&base.to_uppercase())
+            .and_then(serde_json::Value::as_str)
+            .map(Decimal::from_str)
+            .transpose()
+            .map_err(|e| ArgusError::CexApiError(format!("Failed to parse volume: {e}")))?;
+
+        Ok(CexPrice {
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
+            price,
+            volume_24h: volume,
+            timestamp: response
+                .volume
+                .get("timestamp")
+                .and_then(serde_json::Value::as_i64)
+                .and_then(DateTime::from_timestamp_millis)
+                .unwrap_or_else(Utc::now),
+        })
+    }
+
+    async fn get_order_book(&self, base: &str, quote: &str, depth: usize) -> Result<OrderBook> {
+        let url = format!(
+            "https://api.gemini.com/v1/book/{}?limit_bids={depth}&limit_asks={depth}",
+            symbols::GEMINI.symbol(base, quote)
+        );
+
+        let response = self
+            .retry
+            .retry(self.name(), "order_book", || async {
+                self.client
+                    .get(&url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<GeminiBookResponse>()
+                    .await
+                    .map_err(|e| {
+                        ArgusError::CexApiError(format!(
+                            "Failed to parse Gemini order book response: {e}"
+                        ))
+                    })
+            })
+            .await?;
+
+        let levels = |rows: &[GeminiLevel], descending| {
+            parse_levels(
+                self.name(),
+                rows.iter()
+                    .map(|level| (level.price.as_str(), level.amount.as_str())),
+                descending,
+                depth,
+            )
+        };
+
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
+            bids: levels(&response.bids, true)?,
+            asks: levels(&response.asks, false)?,
+            timestamp: Utc::now(),
+        })
+    }
+}
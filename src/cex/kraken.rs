@@ -2,29 +2,43 @@
  * Kraken CEX client implementation
  */
 
-use crate::cex::CexClient;
-use crate::models::{ArgusError, CexPrice, Result};
+use crate::cex::stream::{json_decimal, TickerFeed, TickerUpdate};
+use crate::cex::{http_client, parse_levels, symbols, CexClient};
+use crate::models::{ArgusError, CexPrice, OrderBook, Result};
+use crate::retry::RetryPolicy;
 use async_trait::async_trait;
 use chrono::Utc;
 use reqwest::Client;
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use serde_json::Value;
 use std::collections::HashMap;
 use std::str::FromStr;
 
 pub struct KrakenClient {
     client: Client,
+    retry: RetryPolicy,
 }
 
 #[derive(Debug, Deserialize)]
-struct KrakenResponse {
+struct KrakenResponse<T> {
     error: Vec<String>,
-    result: Option<HashMap<String, KrakenTicker>>,
+    result: Option<HashMap<String, T>>,
 }
 
 #[derive(Debug, Deserialize)]
 struct KrakenTicker {
+    /// Last trade: `[price, lot volume]`
     c: Vec<String>,
+    /// Volume: `[today, last 24 hours]`
+    v: Vec<String>,
+}
+
+/// Levels are `[price, volume, timestamp]`
+#[derive(Debug, Deserialize)]
+struct KrakenDepth {
+    bids: Vec<(String, String, i64)>,
+    asks: Vec<(String, String, i64)>,
 }
 
 impl Default for KrakenClient {
@@ -36,58 +50,63 @@ impl Default for KrakenClient {
 impl KrakenClient {
     #[must_use]
     pub fn new() -> Self {
+        Self::with_retry_policy(RetryPolicy::default())
+    }
+
+    #[must_use]
+    pub fn with_retry_policy(retry: RetryPolicy) -> Self {
         Self {
-            client: Client::new(),
+            client: http_client(&retry),
+            retry,
         }
     }
 
-    fn format_pair(base: &str, quote: &str) -> String {
-        let base_formatted = if base.to_uppercase() == "ETH" {
-            "ETH".to_string()
-        } else {
-            base.to_uppercase()
-        };
-        let quote_formatted = if quote.to_uppercase() == "USDC" {
-            "USDC".to_string()
-        } else {
-            quote.to_uppercase()
-        };
-        format!("{base_formatted}{quote_formatted}")
+    /// The single pair's entry of a successful response, which Kraken keys by its own
+    /// pair name.
+    fn first_result<T>(response: KrakenResponse<T>, data: &str) -> Result<T> {
+        if !response.error.is_empty() {
+            return Err(ArgusError::CexApiError(format!(
+                "Kraken API error: {:?}",
+                response.error
+            )));
+        }
+
+        response
+            .result
+            .ok_or_else(|| ArgusError::CexApiError("No result in Kraken response".to_string()))?
+            .into_values()
+            .next()
+            .ok_or_else(|| ArgusError::CexApiError(format!("No {data} data found")))
     }
 }
 
 #[async_trait]
 impl CexClient for KrakenClient {
+    fn name(&self) -> &'static str {
+        "Kraken"
+    }
+
     async fn get_spot_price(&self, base: &str, quote: &str) -> Result<CexPrice> {
-        let pair = Self::format_pair(base, quote);
+        let pair = symbols::KRAKEN.symbol(base, quote);
         let url = format!("https://api.kraken.com/0/public/Ticker?pair={pair}");
 
         let response = self
-            .client
-            .get(&url)
-            .send()
-            .await?
-            .json::<KrakenResponse>()
-            .await
-            .map_err(|e| {
-                ArgusError::CexApiError(format!("Failed to parse Kraken response: {e}"))
-            })?;
+            .retry
+            .retry(self.name(), "ticker", || async {
+                self.client
+                    .get(&url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<KrakenResponse<KrakenTicker>>()
+                    .await
+                    .map_err(|e| {
+                        ArgusError::CexApiError(format!("Failed to parse Kraken response: {e}"))
+                    })
+            })
+            .await?;
 
-        if !response.error.is_empty() {
-            return Err(ArgusError::CexApiError(format!(
-                "Kraken API error: {:?}",
-                response.error
-            )));
-        }
-
-        let result = response
-            .result
-            .ok_or_else(|| ArgusError::CexApiError("No result in Kraken response".to_string()))?;
-
-        let ticker = result
-            .values()
-            .next()
-            .ok_or_else(|| ArgusError::CexApiError("No ticker data found".to_string()))?;
+        let ticker = Self::first_result(response, "ticker")?;
 
         let price_str = ticker
             .c
@@ -96,12 +115,151 @@ impl CexClient for KrakenClient {
 
         let price = Decimal::from_str(price_str)
             .map_err(|e| ArgusError::CexApiError(format!("Failed to parse price: {e}")))?;
+        let volume = ticker
+            .v
+            .get(1)
+            .map(|volume| Decimal::from_str(volume))
+            .transpose()
+            .map_err(|e| ArgusError::CexApiError(format!("Failed to parse volume: {e}")))?;
 
         Ok(CexPrice {
-            exchange: "Kraken".to_string(),
-            pair: format!("{}/{}", base.to_uppercase(), quote.to_uppercase()),
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
             price,
+            volume_24h: volume,
             timestamp: Utc::now(),
         })
     }
+
+    async fn get_order_book(&self, base: &str, quote: &str, depth: usize) -> Result<OrderBook> {
+        let pair = symbols::KRAKEN.symbol(base, quote);
+        // Kraken serves at most 500 levels per side
+        let count = depth.clamp(1, 500);
+        let url = format!("https://api.kraken.com/0/public/Depth?pair={pair}&count={count}");
+
+        let response = self
+            .retry
+            .retry(self.name(), "order_book", || async {
+                self.client
+                    .get(&url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<KrakenResponse<KrakenDepth>>()
+                    .await
+                    .map_err(|e| {
+                        ArgusError::CexApiError(format!(
+                            "Failed to parse Kraken depth response: {e}"
+                        ))
+                    })
+            })
+            .await?;
+
+        let book = Self::first_result(response, "order book")?;
+        let levels = |rows: &[(String, String, i64)], descending| {
+            parse_levels(
+                self.name(),
+                rows.iter()
+                    .map(|(price, volume, _)| (price.as_str(), volume.as_str())),
+                descending,
+                depth,
+            )
+        };
+
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
+            bids: levels(&book.bids, true)?,
+            asks: levels(&book.asks, false)?,
+            timestamp: Utc::now(),
+        })
+    }
+}
+
+/// WebSocket v2 ticker channel, pushed on every change of the best bid or offer so the
+/// price stays live on thinly traded pairs. Tickers carry no timestamp, so prices are
+/// stamped when received rather than with the time of the last trade.
+pub struct KrakenTickerFeed {
+    symbol: String,
+}
+
+impl KrakenTickerFeed {
+    #[must_use]
+    pub fn new(base: &str, quote: &str) -> Self {
+        Self {
+            symbol: symbols::KRAKEN_WS.symbol(base, quote),
+        }
+    }
+}
+
+impl TickerFeed for KrakenTickerFeed {
+    fn url(&self) -> String {
+        "wss://ws.kraken.com/v2".to_string()
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        vec![serde_json::json!({
+            "method": "subscribe",
+            "params": {
+                "channel": "ticker",
+                "symbol": [self.symbol],
+                "event_trigger": "bbo",
+            },
+        })
+        .to_string()]
+    }
+
+    fn parse(&self, message: &str) -> Result<Option<TickerUpdate>> {
+        let message: Value = serde_json::from_str(message)?;
+        if message["success"] == false {
+            return Err(ArgusError::CexApiError(format!(
+                "Kraken stream error: {}",
+                message["error"]
+            )));
+        }
+
+        if message["channel"] != "ticker" {
+            return Ok(None);
+        }
+
+        Ok(message["data"]
+            .as_array()
+            .and_then(|tickers| tickers.first())
+            .map(|ticker| TickerUpdate {
+                price: json_decimal(&ticker["last"]),
+                volume_24h: json_decimal(&ticker["volume"]),
+                timestamp: None,
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ticker_prices_are_stamped_on_receipt() {
+        let feed = KrakenTickerFeed::new("ETH", "USDC");
+        let update = feed
+            .parse(
+                r#"{"channel":"ticker","type":"update","data":[{"symbol":"ETH/USDC","bid":3000.1,"ask":3000.6,"last":2998.5,"volume":152.25}]}"#,
+            )
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(update.price, Some(Decimal::new(29_985, 1)));
+        assert_eq!(update.volume_24h, Some(Decimal::new(15_225, 2)));
+        assert_eq!(update.timestamp, None);
+    }
+
+    #[test]
+    fn ignores_other_channels_and_reports_errors() {
+        let feed = KrakenTickerFeed::new("ETH", "USDC");
+        assert!(feed.parse(r#"{"channel":"heartbeat"}"#).unwrap().is_none());
+        assert!(feed
+            .parse(
+                r#"{"method":"subscribe","success":false,"error":"Currency pair not supported"}"#
+            )
+            .is_err());
+    }
 }
@@ -2,28 +2,230 @@
  * CEX price fetcher module for getting reference prices
  */
 
+mod aggregate;
 mod binance;
+mod bitstamp;
+mod bybit;
 mod coinbase;
+mod gemini;
 mod kraken;
+mod okx;
+pub mod stream;
+pub mod symbols;
 
-use crate::config::CexProvider;
-use crate::models::{CexPrice, Result};
+use crate::config::{CexConfig, CexProvider};
+use crate::metrics::Metrics;
+use crate::models::{ArgusError, CexPrice, OrderBook, OrderBookLevel, ReferencePrice, Result};
+use crate::retry::RetryPolicy;
 use async_trait::async_trait;
+use reqwest::Client;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::time::Instant;
+use stream::FeedFactory;
 
-pub use binance::BinanceClient;
-pub use coinbase::CoinbaseClient;
-pub use kraken::KrakenClient;
+pub use aggregate::AggregateCexClient;
+pub use binance::{BinanceClient, BinanceTickerFeed};
+pub use bitstamp::BitstampClient;
+pub use bybit::BybitClient;
+pub use coinbase::{CoinbaseClient, CoinbaseTickerFeed};
+pub use gemini::GeminiClient;
+pub use kraken::{KrakenClient, KrakenTickerFeed};
+pub use okx::OkxClient;
+pub use stream::StreamingCexClient;
 
 #[async_trait]
 pub trait CexClient: Send + Sync {
+    fn name(&self) -> &'static str;
     async fn get_spot_price(&self, base: &str, quote: &str) -> Result<CexPrice>;
+
+    /// Reference price with the quotes behind it; a single exchange's spot price unless
+    /// the client aggregates several.
+    async fn get_reference_price(&self, base: &str, quote: &str) -> Result<ReferencePrice> {
+        Ok(ReferencePrice::from(
+            self.get_spot_price(base, quote).await?,
+        ))
+    }
+
+    /// Top `depth` levels on each side of the `base`/`quote` order book.
+    async fn get_order_book(&self, base: &str, quote: &str, depth: usize) -> Result<OrderBook>;
 }
 
+/// Client for the configured exchanges: the exchange itself when only one is configured,
+/// an [`AggregateCexClient`] over all of them otherwise.
 #[must_use]
-pub fn create_cex_client(provider: &CexProvider) -> Box<dyn CexClient> {
+pub fn create_cex_client(config: &CexConfig, retry: RetryPolicy) -> Box<dyn CexClient> {
+    let mut clients: Vec<Box<dyn CexClient>> = config
+        .providers
+        .iter()
+        .map(|provider| -> Box<dyn CexClient> {
+            Box::new(TimedCexClient {
+                inner: create_provider_client(provider, config.streaming, retry),
+            })
+        })
+        .collect();
+
+    if clients.len() == 1 {
+        clients.remove(0)
+    } else {
+        Box::new(AggregateCexClient::new(clients, config))
+    }
+}
+
+/// REST client of `provider`, wrapped in a [`StreamingCexClient`] when `streaming` is set
+/// and the exchange has a ticker stream.
+fn create_provider_client(
+    provider: &CexProvider,
+    streaming: bool,
+    retry: RetryPolicy,
+) -> Box<dyn CexClient> {
+    let stream = |rest: Box<dyn CexClient>, feed: FeedFactory| -> Box<dyn CexClient> {
+        if streaming {
+            Box::new(StreamingCexClient::new(rest, feed))
+        } else {
+            rest
+        }
+    };
+
     match provider {
-        CexProvider::Coinbase => Box::new(CoinbaseClient::new()),
-        CexProvider::Kraken => Box::new(KrakenClient::new()),
-        CexProvider::Binance => Box::new(BinanceClient::new()),
+        CexProvider::Coinbase => stream(
+            Box::new(CoinbaseClient::with_retry_policy(retry)),
+            |base, quote| Box::new(CoinbaseTickerFeed::new(base, quote)),
+        ),
+        CexProvider::Kraken => stream(
+            Box::new(KrakenClient::with_retry_policy(retry)),
+            |base, quote| Box::new(KrakenTickerFeed::new(base, quote)),
+        ),
+        CexProvider::Binance => stream(
+            Box::new(BinanceClient::with_retry_policy(retry)),
+            |base, quote| Box::new(BinanceTickerFeed::new(base, quote)),
+        ),
+        CexProvider::Okx => Box::new(OkxClient::with_retry_policy(retry)),
+        CexProvider::Bybit => Box::new(BybitClient::with_retry_policy(retry)),
+        CexProvider::Bitstamp => Box::new(BitstampClient::with_retry_policy(retry)),
+        CexProvider::Gemini => Box::new(GeminiClient::with_retry_policy(retry)),
+    }
+}
+
+/// Records the latency of every request to one exchange under its name, so the
+/// exchange is timed once whether or not it sits behind an [`AggregateCexClient`].
+struct TimedCexClient {
+    inner: Box<dyn CexClient>,
+}
+
+#[async_trait]
+impl CexClient for TimedCexClient {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn get_spot_price(&self, base: &str, quote: &str) -> Result<CexPrice> {
+        let started = Instant::now();
+        let result = self.inner.get_spot_price(base, quote).await;
+        Metrics::global().observe_cex_latency(self.name(), started);
+        result
+    }
+
+    async fn get_order_book(&self, base: &str, quote: &str, depth: usize) -> Result<OrderBook> {
+        let started = Instant::now();
+        let result = self.inner.get_order_book(base, quote, depth).await;
+        Metrics::global().observe_cex_latency(self.name(), started);
+        result
+    }
+}
+
+/// HTTP client whose requests time out after the policy's per-request timeout. The user
+/// agent is required by Coinbase's exchange API, which rejects requests without one.
+fn http_client(retry: &RetryPolicy) -> Client {
+    Client::builder()
+        .timeout(retry.timeout)
+        .user_agent(concat!("argus/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap_or_default()
+}
+
+/// Parses `(price, quantity)` rows into levels sorted best first, dropping empty levels
+/// and keeping at most `depth`. Bids sort by descending price, asks by ascending.
+fn parse_levels<'a>(
+    exchange: &str,
+    rows: impl IntoIterator<Item = (&'a str, &'a str)>,
+    descending: bool,
+    depth: usize,
+) -> Result<Vec<OrderBookLevel>> {
+    let parse = |value: &str| {
+        Decimal::from_str(value).map_err(|e| {
+            ArgusError::CexApiError(format!("Failed to parse {exchange} order book level: {e}"))
+        })
+    };
+
+    let mut levels = rows
+        .into_iter()
+        .map(|(price, quantity)| {
+            Ok(OrderBookLevel {
+                price: parse(price)?,
+                quantity: parse(quantity)?,
+            })
+        })
+        .filter(|level| {
+            level
+                .as_ref()
+                .map_or(true, |level| !level.quantity.is_zero())
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if descending {
+        levels.sort_by_key(|level| std::cmp::Reverse(level.price));
+    } else {
+        levels.sort_by_key(|level| level.price);
+    }
+    levels.truncate(depth);
+    Ok(levels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROWS: [(&str, &str); 4] = [
+        ("3001.5", "0.2"),
+        ("3003", "1"),
+        ("2999", "0"),
+        ("3000.25", "4"),
+    ];
+
+    fn prices(levels: &[OrderBookLevel]) -> Vec<Decimal> {
+        levels.iter().map(|level| level.price).collect()
+    }
+
+    #[test]
+    fn sorts_bids_descending() {
+        let levels = parse_levels("Test", ROWS, true, 10).unwrap();
+        assert_eq!(
+            prices(&levels),
+            ["3003", "3001.5", "3000.25"].map(|price| Decimal::from_str(price).unwrap())
+        );
+    }
+
+    #[test]
+    fn sorts_asks_ascending_and_truncates_to_depth() {
+        let levels = parse_levels("Test", ROWS, false, 2).unwrap();
+        assert_eq!(
+            prices(&levels),
+            ["3000.25", "3001.5"].map(|price| Decimal::from_str(price).unwrap())
+        );
+        assert_eq!(levels[0].quantity, Decimal::from(4));
+    }
+
+    #[test]
+    fn drops_empty_levels() {
+        let levels = parse_levels("Test", ROWS, false, 10).unwrap();
+        assert!(levels.iter().all(|level| !level.quantity.is_zero()));
+        assert_eq!(levels.len(), 3);
+    }
+
+    #[test]
+    fn rejects_unparseable_levels() {
+        let error = parse_levels("Test", [("3000", "abc")], false, 10).unwrap_err();
+        assert!(error.to_string().contains("Test order book level"));
     }
 }
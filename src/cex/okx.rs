@@ -0,0 +1,158 @@
+/*
+ * OKX CEX client implementation
+ */
+
+use crate::cex::{http_client, parse_levels, symbols, CexClient};
+use crate::models::{ArgusError, CexPrice, OrderBook, Result};
+use crate::retry::RetryPolicy;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::str::FromStr;
+
+pub struct OkxClient {
+    client: Client,
+    retry: RetryPolicy,
+}
+
+#[derive(Debug, Deserialize)]
+struct OkxResponse<T> {
+    code: String,
+    msg: String,
+    data: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OkxTicker {
+    last: String,
+    /// Base asset volume of the last 24 hours
+    vol24h: String,
+    /// Milliseconds, as a string
+    ts: String,
+}
+
+/// Levels are `[price, size, deprecated, order count]`
+#[derive(Debug, Deserialize)]
+struct OkxBook {
+    bids: Vec<Vec<String>>,
+    asks: Vec<Vec<String>>,
+}
+
+impl Default for OkxClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OkxClient {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_retry_policy(RetryPolicy::default())
+    }
+
+    #[must_use]
+    pub fn with_retry_policy(retry: RetryPolicy) -> Self {
+        Self {
+            client: http_client(&retry),
+            retry,
+        }
+    }
+
+    /// First entry of a successful response's `data`.
+    async fn get_data<T: DeserializeOwned>(&self, url: &str, operation: &str) -> Result<T> {
+        let response = self
+            .retry
+            .retry(self.name(), operation, || async {
+                self.client
+                    .get(url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<OkxResponse<T>>()
+                    .await
+                    .map_err(|e| {
+                        ArgusError::CexApiError(format!("Failed to parse OKX response: {e}"))
+                    })
+            })
+            .await?;
+
+        if response.code != "0" {
+            return Err(ArgusError::CexApiError(format!(
+                "OKX API error {}: {}",
+                response.code, response.msg
+            )));
+        }
+
+        response
+            .data
+            .into_iter()
+            .next()
+            .ok_or_else(|| ArgusError::CexApiError(format!("No {operation} data found")))
+    }
+}
+
+#[async_trait]
+impl CexClient for OkxClient {
+    fn name(&self) -> &'static str {
+        "OKX"
+    }
+
+    async fn get_spot_price(&self, base: &str, quote: &str) -> Result<CexPrice> {
+        let url = format!(
+            "https://www.okx.com/api/v5/market/ticker?instId={}",
+            symbols::OKX.symbol(base, quote)
+        );
+        let ticker: OkxTicker = self.get_data(&url, "ticker").await?;
+
+        let price = Decimal::from_str(&ticker.last)
+            .map_err(|e| ArgusError::CexApiError(format!("Failed to parse price: {e}")))?;
+        let volume = Decimal::from_str(&ticker.vol24h)
+            .map_err(|e| ArgusError::CexApiError(format!("Failed to parse volume: {e}")))?;
+
+        Ok(CexPrice {
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
+            price,
+            volume_24h: Some(volume),
+            timestamp: ticker
+                .ts
+                .parse()
+                .ok()
+                .and_then(DateTime::from_timestamp_millis)
+                .unwrap_or_else(Utc::now),
+        })
+    }
+
+    async fn get_order_book(&self, base: &str, quote: &str, depth: usize) -> Result<OrderBook> {
+        // OKX serves at most 400 levels per side
+        let url = format!(
+            "https://www.okx.com/api/v5/market/books?instId={}&sz={}",
+            symbols::OKX.symbol(base, quote),
+            depth.clamp(1, 400)
+        );
+        let book: OkxBook = self.get_data(&url, "order_book").await?;
+
+        let levels = |rows: &[Vec<String>], descending| {
+            parse_levels(
+                self.name(),
+                rows.iter().filter_map(|row| match row.as_slice() {
+                    [price, size, ..] => Some((price.as_str(), size.as_str())),
+                    _ => None,
+                }),
+                descending,
+                depth,
+            )
+        };
+
+        Ok(OrderBook {
+            exchange: self.name().to_string(),
+            pair: symbols::display_pair(base, quote),
+            bids: levels(&book.bids, true)?,
+            asks: levels(&book.asks, false)?,
+            timestamp: Utc::now(),
+        })
+    }
+}
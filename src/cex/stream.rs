@@ -0,0 +1,254 @@
+/*
+ * WebSocket ticker streams keeping the latest CEX price of each pair in memory
+ */
+
+use crate::cex::{symbols, CexClient};
+use crate::models::{ArgusError, CexPrice, OrderBook, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{SinkExt, StreamExt};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+// Silence after which a connection is considered dead; every feed sends at least a
+// heartbeat well within it
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Reconnect delays double from the first to the last while connections keep failing
+const INITIAL_RECONNECT_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_mins(1);
+
+/// Exchange-specific half of a ticker stream: where to connect, what to subscribe to and
+/// how to read the messages.
+pub trait TickerFeed: Send + Sync + 'static {
+    fn url(&self) -> String;
+
+    /// Messages sent right after connecting.
+    fn subscriptions(&self) -> Vec<String>;
+
+    /// Reads one text message; `None` for messages that carry no price data, such as
+    /// heartbeats and subscription acknowledgements.
+    fn parse(&self, message: &str) -> Result<Option<TickerUpdate>>;
+}
+
+/// Fields of the latest price that one message updates.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TickerUpdate {
+    pub price: Option<Decimal>,
+    pub volume_24h: Option<Decimal>,
+    /// Exchange time of the event that produced `price`
+    pub timestamp: Option<DateTime<Utc>>,
+}
+
+/// Latest price of one pair as pushed by the exchange.
+pub struct PriceStream {
+    exchange: &'static str,
+    pair: String,
+    latest: RwLock<Option<CexPrice>>,
+    /// Whether the connection is up and has delivered data since it was opened
+    live: AtomicBool,
+}
+
+impl PriceStream {
+    /// Connects `feed` in the background and keeps reconnecting, with exponential
+    /// backoff, for as long as the returned stream is alive.
+    #[must_use = "the stream stops once the returned handle is dropped"]
+    pub fn spawn(exchange: &'static str, pair: String, feed: Box<dyn TickerFeed>) -> Arc<Self> {
+        let stream = Arc::new(Self {
+            exchange,
+            pair,
+            latest: RwLock::new(None),
+            live: AtomicBool::new(false),
+        });
+        tokio::spawn(run(Arc::downgrade(&stream), feed));
+        stream
+    }
+
+    /// Latest price, unless the connection is down or has not delivered one yet.
+    pub fn latest(&self) -> Option<CexPrice> {
+        if !self.live.load(Ordering::Relaxed) {
+            return None;
+        }
+        self.latest.read().unwrap().clone()
+    }
+
+    fn apply(&self, update: TickerUpdate) {
+        let mut latest = self.latest.write().unwrap();
+        match (latest.as_mut(), update.price) {
+            (Some(current), price) => {
+                if let Some(price) = price {
+                    current.price = price;
+                    current.timestamp = update.timestamp.unwrap_or_else(Utc::now);
+                }
+                if update.volume_24h.is_some() {
+                    current.volume_24h = update.volume_24h;
+                }
+            }
+            (None, Some(price)) => {
+                *latest = Some(CexPrice {
+                    exchange: self.exchange.to_string(),
+                    pair: self.pair.clone(),
+                    price,
+                    volume_24h: update.volume_24h,
+                    timestamp: update.timestamp.unwrap_or_else(Utc::now),
+                });
+            }
+            // Volume alone is not worth serving before the first price
+            (None, None) => return,
+        }
+        self.live.store(true, Ordering::Relaxed);
+    }
+}
+
+async fn run(stream: Weak<PriceStream>, feed: Box<dyn TickerFeed>) {
+    let mut backoff = INITIAL_RECONNECT_BACKOFF;
+
+    loop {
+        let Some(current) = stream.upgrade() else {
+            return;
+        };
+        let (exchange, pair) = (current.exchange, current.pair.clone());
+        drop(current);
+
+        match follow(&stream, feed.as_ref()).await {
+            Ok(()) => info!("{} {} stream closed, reconnecting", exchange, pair),
+            Err(e) => warn!("{} {} stream failed: {}", exchange, pair, e),
+        }
+
+        let Some(current) = stream.upgrade() else {
+            return;
+        };
+        // A connection that delivered data resets the backoff
+        if current.live.swap(false, Ordering::Relaxed) {
+            backoff = INITIAL_RECONNECT_BACKOFF;
+        }
+        drop(current);
+
+        tokio::time::sleep(backoff).await;
+        backoff = backoff.saturating_mul(2).min(MAX_RECONNECT_BACKOFF);
+    }
+}
+
+/// Connects, subscribes and applies messages until the connection closes, goes idle or
+/// the stream is dropped.
+async fn follow(stream: &Weak<PriceStream>, feed: &dyn TickerFeed) -> Result<()> {
+    let url = feed.url();
+    let (mut socket, _) = tokio_tungstenite::connect_async(url.as_str())
+        .await
+        .map_err(|e| ArgusError::CexApiError(format!("Failed to connect to {url}: {e}")))?;
+
+    for subscription in feed.subscriptions() {
+        socket
+            .send(Message::Text(subscription))
+            .await
+            .map_err(|e| ArgusError::CexApiError(format!("Failed to subscribe: {e}")))?;
+    }
+    debug!("Connected to {}", url);
+
+    loop {
+        let message = match tokio::time::timeout(STREAM_IDLE_TIMEOUT, socket.next()).await {
+            Ok(Some(message)) => {
+                message.map_err(|e| ArgusError::CexApiError(format!("Stream error: {e}")))?
+            }
+            Ok(None) => return Ok(()),
+            Err(_) => {
+                return Err(ArgusError::CexApiError(format!(
+                    "No message for {}s",
+                    STREAM_IDLE_TIMEOUT.as_secs()
+                )))
+            }
+        };
+
+        let Some(stream) = stream.upgrade() else {
+            return Ok(());
+        };
+        match message {
+            Message::Text(text) => {
+                if let Some(update) = feed.parse(&text)? {
+                    stream.apply(update);
+                }
+            }
+            Message::Close(_) => return Ok(()),
+            _ => {}
+        }
+    }
+}
+
+/// Decimal from a JSON string or number.
+pub(crate) fn json_decimal(value: &Value) -> Option<Decimal> {
+    match value {
+        Value::String(s) => Decimal::from_str(s).ok(),
+        Value::Number(n) => {
+            let n = n.to_string();
+            Decimal::from_str(&n)
+                .or_else(|_| Decimal::from_scientific(&n))
+                .ok()
+        }
+        _ => None,
+    }
+}
+
+/// Builds the feed of a `base`/`quote` pair.
+pub type FeedFactory = fn(&str, &str) -> Box<dyn TickerFeed>;
+
+/// Serves spot prices from WebSocket streams, opened per pair on first request. Until a
+/// pair's stream is live, and whenever it is down, prices come from the REST client,
+/// which also serves order books.
+pub struct StreamingCexClient {
+    rest: Box<dyn CexClient>,
+    feed: FeedFactory,
+    streams: RwLock<HashMap<String, Arc<PriceStream>>>,
+}
+
+impl StreamingCexClient {
+    #[must_use]
+    pub fn new(rest: Box<dyn CexClient>, feed: FeedFactory) -> Self {
+        Self {
+            rest,
+            feed,
+            streams: RwLock::new(HashMap::new()),
+        }
+    }
+
+    fn stream(&self, base: &str, quote: &str) -> Arc<PriceStream> {
+        let pair = symbols::display_pair(base, quote);
+        if let Some(stream) = self.streams.read().unwrap().get(&pair) {
+            return stream.clone();
+        }
+
+        self.streams
+            .write()
+            .unwrap()
+            .entry(pair.clone())
+            .or_insert_with(|| {
+                info!("Opening {} {} price stream", self.rest.name(), pair);
+                PriceStream::spawn(self.rest.name(), pair, (self.feed)(base, quote))
+            })
+            .clone()
+    }
+}
+
+#[async_trait]
+impl CexClient for StreamingCexClient {
+    fn name(&self) -> &'static str {
+        self.rest.name()
+    }
+
+    async fn get_spot_price(&self, base: &str, quote: &str) -> Result<CexPrice> {
+        match self.stream(base, quote).latest() {
+            Some(price) => Ok(price),
+            None => self.rest.get_spot_price(base, quote).await,
+        }
+    }
+
+    async fn get_order_book(&self, base: &str, quote: &str, depth: usize) -> Result<OrderBook> {
+        self.rest.get_order_book(base, quote, depth).await
+    }
+}
@@ -0,0 +1,89 @@
+/*
+ * Trading pair symbol conventions of each supported exchange
+ */
+
+/// How an exchange spells a trading pair in its API.
+#[derive(Debug, Clone, Copy)]
+pub struct SymbolMapping {
+    separator: &'static str,
+    lowercase: bool,
+    /// Asset codes the exchange lists under another code, as `(asset, exchange code)`
+    aliases: &'static [(&'static str, &'static str)],
+}
+
+impl SymbolMapping {
+    const fn new(separator: &'static str) -> Self {
+        Self {
+            separator,
+            lowercase: false,
+            aliases: &[],
+        }
+    }
+
+    const fn lowercase(self) -> Self {
+        Self {
+            lowercase: true,
+            ..self
+        }
+    }
+
+    const fn aliases(self, aliases: &'static [(&'static str, &'static str)]) -> Self {
+        Self { aliases, ..self }
+    }
+
+    /// Exchange symbol of the `base`/`quote` pair.
+    #[must_use]
+    pub fn symbol(&self, base: &str, quote: &str) -> String {
+        let symbol = format!(
+            "{}{}{}",
+            self.asset(base),
+            self.separator,
+            self.asset(quote)
+        );
+        if self.lowercase {
+            symbol.to_lowercase()
+        } else {
+            symbol
+        }
+    }
+
+    fn asset(&self, asset: &str) -> String {
+        let asset = asset.to_uppercase();
+        self.aliases
+            .iter()
+            .find(|(from, _)| *from == asset)
+            .map_or(asset, |(_, to)| (*to).to_string())
+    }
+}
+
+/// `ETHUSDC`
+pub const BINANCE: SymbolMapping = SymbolMapping::new("");
+
+/// `ETH-USD`; USDC trades on the USD books, which Coinbase converts 1:1
+pub const COINBASE: SymbolMapping = SymbolMapping::new("-").aliases(&[("USDC", "USD")]);
+
+/// `ETHUSDC`; Bitcoin is listed as XBT
+pub const KRAKEN: SymbolMapping = SymbolMapping::new("").aliases(&[("BTC", "XBT")]);
+
+/// `ETH/USDC`, as used by the WebSocket v2 API, which lists Bitcoin as BTC
+pub const KRAKEN_WS: SymbolMapping = SymbolMapping::new("/");
+
+/// `ETH-USDC`
+pub const OKX: SymbolMapping = SymbolMapping::new("-");
+
+/// `ETHUSDC`
+pub const BYBIT: SymbolMapping = SymbolMapping::new("");
+
+/// `ethusdc`
+pub const BITSTAMP: SymbolMapping = SymbolMapping::new("").lowercase();
+
+/// `ethusd`; Gemini quotes ETH against USD, which it converts 1:1 with USDC
+pub const GEMINI: SymbolMapping = SymbolMapping::new("")
+    .lowercase()
+    .aliases(&[("USDC", "USD")]);
+
+/// Exchange-independent `BASE/QUOTE` name used in responses.
+#[must_use]
+pub fn display_pair(base: &str, quote: &str) -> String {
+    format!("{}/{}", base.to_uppercase(), quote.to_uppercase())
+}
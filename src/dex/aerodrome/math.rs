@@ -0,0 +1,294 @@
+/*
+ * Aerodrome pool curve math (volatile x*y=k and stable x^3*y + x*y^3 = k)
+ */
+
+use crate::models::{ArgusError, Result};
+use ethers::types::U256;
+
+/// Aerodrome fees are expressed in basis points.
+pub const FEE_DENOMINATOR: u64 = 10_000;
+
+// _get_y gives up after this many Newton iterations, mirroring the pool contract
+const MAX_NEWTON_ITERATIONS: usize = 255;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CurveParams {
+    pub stable: bool,
+    /// 10^decimals of token0, as returned by `metadata()`
+    pub decimals0: U256,
+    /// 10^decimals of token1, as returned by `metadata()`
+    pub decimals1: U256,
+}
+
+fn e18() -> U256 {
+    U256::exp10(18)
+}
+
+/// Output amount for an exact input, matching `Pool.getAmountOut` including the fee.
+pub fn get_amount_out(
+    amount_in: U256,
+    token0_in: bool,
+    reserve0: U256,
+    reserve1: U256,
+    fee_bps: u64,
+    params: CurveParams,
+) -> Result<U256> {
+    if reserve0.is_zero() || reserve1.is_zero() {
+        return Err(ArgusError::CalculationError(
+            "Insufficient liquidity".to_string(),
+        ));
+    }
+
+    let amount_in = amount_in - amount_in * U256::from(fee_bps) / U256::from(FEE_DENOMINATOR);
+
+    if params.stable {
+        let xy = k(reserve0, reserve1, params);
+        let reserve0 = reserve0 * e18() / params.decimals0;
+        let reserve1 = reserve1 * e18() / params.decimals1;
+
+        let (reserve_a, reserve_b) = if token0_in {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        };
+        let amount_in = if token0_in {
+            amount_in * e18() / params.decimals0
+        } else {
+            amount_in * e18() / params.decimals1
+        };
+
+        let y = reserve_b
+            .checked_sub(get_y(amount_in + reserve_a, xy, reserve_b, params)?)
+            .ok_or_else(|| {
+                ArgusError::CalculationError("Stable swap output underflow".to_string())
+            })?;
+
+        let decimals_out = if token0_in {
+            params.decimals1
+        } else {
+            params.decimals0
+        };
+        Ok(y * decimals_out / e18())
+    } else {
+        let (reserve_a, reserve_b) = if token0_in {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        };
+        Ok(amount_in * reserve_b / (reserve_a + amount_in))
+    }
+}
+
+/// Marginal price of token0 in token1, in human units (ignoring fees).
+#[must_use]
+pub fn spot_price(reserve0: f64, reserve1: f64, stable: bool) -> f64 {
+    if stable {
+        // dy/dx of x^3*y + x*y^3 = k
+        (3.0 * reserve0 * reserve0 * reserve1 + reserve1 * reserve1 * reserve1)
+            / (reserve0 * reserve0 * reserve0 + 3.0 * reserve0 * reserve1 * reserve1)
+    } else {
+        reserve1 / reserve0
+    }
+}
+
+fn k(x: U256, y: U256, params: CurveParams) -> U256 {
+    if params.stable {
+        let x = x * e18() / params.decimals0;
+        let y = y * e18() / params.decimals1;
+        let a = x * y / e18();
+        let b = x * x / e18() + y * y / e18();
+        a * b / e18()
+    } else {
+        x * y
+    }
+}
+
+fn f(x0: U256, y: U256) -> U256 {
+    let a = x0 * y / e18();
+    let b = x0 * x0 / e18() + y * y / e18();
+    a * b / e18()
+}
+
+fn d(x0: U256, y: U256) -> U256 {
+    U256::from(3) * x0 * (y * y / e18()) / e18() + (x0 * x0 / e18()) * x0 / e18()
+}
+
+// Newton's method for y in f(x0, y) = xy; kept step-for-step identical to the
+// contract, including its use of `k` (which re-normalises) in the convergence check.
+// Where the contract would revert on a zero slope or an underflow, this fails instead.
+fn get_y(x0: U256, xy: U256, mut y: U256, params: CurveParams) -> Result<U256> {
+    for _ in 0..MAX_NEWTON_ITERATIONS {
+        let k_current = f(x0, y);
+        let slope = d(x0, y);
+        if slope.is_zero() {
+            return Err(ArgusError::CalculationError(
+                "Stable swap curve is flat at these reserves".to_string(),
+            ));
+        }
+
+        if k_current < xy {
+            let mut dy = (xy - k_current) * e18() / slope;
+            if dy.is_zero() {
+                if k_current == xy {
+                    return Ok(y);
+                }
+                if k(x0, y + 1, params) > xy {
+                    return Ok(y + 1);
+                }
+                dy = U256::one();
+            }
+            y += dy;
+        } else {
+            let mut dy = (k_current - xy) * e18() / slope;
+            if dy.is_zero() {
+                if k_current == xy || y.is_zero() || f(x0, y - 1) < xy {
+                    return Ok(y);
+                }
+                dy = U256::one();
+            }
+            y = y.checked_sub(dy).ok_or_else(|| {
+                ArgusError::CalculationError("Stable swap output underflow".to_string())
+            })?;
+        }
+    }
+
+    Err(ArgusError::CalculationError(
+        "Stable swap did not converge".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Expected amounts follow Pool.getAmountOut's integer arithmetic step for step
+
+    const VOLATILE_WETH_USDC: CurveParams = CurveParams {
+        stable: false,
+        decimals0: U256([1_000_000_000_000_000_000, 0, 0, 0]),
+        decimals1: U256([1_000_000, 0, 0, 0]),
+    };
+
+    const STABLE_USDC_DAI: CurveParams = CurveParams {
+        stable: true,
+        decimals0: U256([1_000_000, 0, 0, 0]),
+        decimals1: U256([1_000_000_000_000_000_000, 0, 0, 0]),
+    };
+
+    const STABLE_18_18: CurveParams = CurveParams {
+        stable: true,
+        decimals0: U256([1_000_000_000_000_000_000, 0, 0, 0]),
+        decimals1: U256([1_000_000_000_000_000_000, 0, 0, 0]),
+    };
+
+    fn u256(value: &str) -> U256 {
+        U256::from_dec_str(value).unwrap()
+    }
+
+    #[test]
+    fn volatile_amount_out() {
+        let reserve0 = u256("5000000000000000000000");
+        let reserve1 = u256("15000000000000");
+
+        let usdc_out = get_amount_out(
+            u256("10000000000000000000"),
+            true,
+            reserve0,
+            reserve1,
+            30,
+            VOLATILE_WETH_USDC,
+        )
+        .unwrap();
+        assert_eq!(usdc_out, u256("29850478146"));
+
+        let weth_out = get_amount_out(
+            u256("30000000000"),
+            false,
+            reserve0,
+            reserve1,
+            30,
+            VOLATILE_WETH_USDC,
+        )
+        .unwrap();
+        assert_eq!(weth_out, u256("9950159382191909332"));
+    }
+
+    #[test]
+    fn stable_amount_out() {
+        let reserve0 = u256("2000000000000");
+        let reserve1 = u256("1900000000000000000000000");
+
+        let dai_out = get_amount_out(
+            u256("1000000000"),
+            true,
+            reserve0,
+            reserve1,
+            5,
+            STABLE_USDC_DAI,
+        )
+        .unwrap();
+        assert_eq!(dai_out, u256("999465277467027905241"));
+
+        let usdc_out = get_amount_out(
+            u256("1000000000000000000000"),
+            false,
+            reserve0,
+            reserve1,
+            5,
+            STABLE_USDC_DAI,
+        )
+        .unwrap();
+        assert_eq!(usdc_out, u256("999532702"));
+
+        // Half the pool's depth moves well past the flat part of the curve
+        let dai_out = get_amount_out(
+            u256("1000000000000"),
+            true,
+            reserve0,
+            reserve1,
+            5,
+            STABLE_USDC_DAI,
+        )
+        .unwrap();
+        assert_eq!(dai_out, u256("929900430187167785014324"));
+    }
+
+    #[test]
+    fn stable_swap_that_does_not_converge_fails() {
+        let result = get_amount_out(
+            U256::from(902_834),
+            true,
+            U256::from(4),
+            u256("692689190590126864284"),
+            0,
+            STABLE_18_18,
+        );
+        assert!(result.unwrap_err().to_string().contains("did not converge"));
+    }
+
+    #[test]
+    fn stable_swap_on_flat_curve_fails() {
+        let result = get_amount_out(
+            U256::from(8118),
+            true,
+            U256::from(74_607),
+            U256::from(262),
+            0,
+            STABLE_18_18,
+        );
+        assert!(result.unwrap_err().to_string().contains("flat"));
+    }
+
+    #[test]
+    fn empty_reserves_fail() {
+        let result = get_amount_out(
+            U256::one(),
+            true,
+            U256::zero(),
+            U256::one(),
+            30,
+            VOLATILE_WETH_USDC,
+        );
+        assert!(result.is_err());
+    }
+}
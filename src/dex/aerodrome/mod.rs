@@ -2,10 +2,17 @@
  * Aerodrome Finance integration module
  */
 
+mod math;
 mod pool;
+mod router;
+mod slipstream;
 
 pub use pool::AerodromeClient;
+pub use slipstream::SlipstreamClient;
 
 pub const POOL_ADDRESS: &str = "0xcDAC0d6c6C59727a65F871236188350531885C43";
+pub const SLIPSTREAM_POOL_ADDRESS: &str = "0xb2cc224c1c9feE385f8ad6a55b4d94E92359DC59";
+pub const ROUTER_ADDRESS: &str = "0xcF77a3Ba9A5CA399B7c97c74d54e5b1Beb874E43";
+pub const SLIPSTREAM_ROUTER_ADDRESS: &str = "0xBE6D8f0d05cC4be24d5167a3eF062215bE6D18a5";
 pub const WETH_ADDRESS: &str = "0x4200000000000000000000000000000000000006";
 pub const USDC_ADDRESS: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
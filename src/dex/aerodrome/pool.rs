@@ -2,21 +2,50 @@
  * Aerodrome Finance pool client implementation
  */
 
-use crate::dex::{DexClient, PoolState, SwapQuote};
+use super::math::{self, CurveParams};
+use super::router::{self, Route};
+use crate::dex::pool_cache::{self, EventSourcedPool, PoolStateCache};
+use crate::dex::{
+    state_override, DexClient, PoolState, SwapCalldata, SwapCalldataParams, SwapQuote,
+};
 use crate::models::{ArgusError, Result};
-use crate::rpc::RpcClient;
+use crate::rpc::{Call3, RpcClient};
 use async_trait::async_trait;
 use ethers::{
-    providers::Middleware,
-    types::{Address, U256},
+    abi::Token,
+    types::{spoof, Address, Filter, Log, H256, U256},
+    utils::keccak256,
 };
 use rust_decimal::Decimal;
 use std::str::FromStr;
 use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+// Emitted by the pool with its new reserves after every swap, mint and burn
+const SYNC_EVENT: &str = "Sync(uint256,uint256)";
 
 pub struct AerodromeClient {
     rpc: Arc<RpcClient>,
     pool_address: Address,
+    metadata: OnceCell<PoolMetadata>,
+    cache: PoolStateCache<PoolSnapshot>,
+}
+
+/// Immutable pool properties, read once and cached.
+#[derive(Debug, Clone, Copy)]
+struct PoolMetadata {
+    curve: CurveParams,
+    token0: Address,
+    token1: Address,
+    factory: Address,
+}
+
+/// Reserves and swap fee of the pool at one block.
+#[derive(Debug, Clone, Copy)]
+pub struct PoolSnapshot {
+    reserve0: U256,
+    reserve1: U256,
+    fee_bps: u64,
 }
 
 impl AerodromeClient {
@@ -24,50 +53,190 @@ impl AerodromeClient {
         let pool_address = Address::from_str(super::POOL_ADDRESS)
             .map_err(|e| ArgusError::ContractError(format!("Invalid pool address: {e}")))?;
 
-        Ok(Self { rpc, pool_address })
+        Ok(Self::with_pool(rpc, pool_address))
     }
 
-    async fn get_reserves(&self) -> Result<(u128, u128)> {
-        let provider = self.rpc.provider();
-        let reserves_selector = ethers::utils::keccak256(b"getReserves()").to_vec();
+    /// Client for an arbitrary Aerodrome v2 pool, stable or volatile.
+    #[must_use]
+    pub fn with_pool(rpc: Arc<RpcClient>, pool_address: Address) -> Self {
+        Self {
+            rpc,
+            pool_address,
+            metadata: OnceCell::new(),
+            cache: PoolStateCache::new(),
+        }
+    }
 
-        let call_data = &reserves_selector[0..4];
+    async fn get_metadata(&self) -> Result<PoolMetadata> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let result = self
+                    .rpc
+                    .multicall(
+                        &[
+                            Call3::function(self.pool_address, "metadata()", &[]),
+                            Call3::function(self.pool_address, "factory()", &[]),
+                        ],
+                        None,
+                        "metadata+factory",
+                    )
+                    .await?;
 
-        let tx = ethers::types::TransactionRequest::new()
-            .to(self.pool_address)
-            .data(ethers::types::Bytes::from(call_data.to_vec()));
+                let metadata = result.output(0, "metadata", 224)?;
+                let factory = result.output(1, "factory", 32)?;
 
-        let result = provider
-            .call(&tx.into(), None)
+                Ok(PoolMetadata {
+                    curve: CurveParams {
+                        decimals0: U256::from_big_endian(&metadata[0..32]),
+                        decimals1: U256::from_big_endian(&metadata[32..64]),
+                        stable: metadata[159] != 0,
+                    },
+                    token0: Address::from_slice(&metadata[172..192]),
+                    token1: Address::from_slice(&metadata[204..224]),
+                    factory: Address::from_slice(&factory[12..32]),
+                })
+            })
             .await
-            .map_err(|e| ArgusError::ContractError(format!("Failed to call getReserves: {e}")))?;
+            .copied()
+    }
 
-        if result.len() < 64 {
+    /// Reads the reserves and the current swap fee from `PoolFactory.getFee(pool, stable)`
+    /// in one batch at `block_number`.
+    async fn read_snapshot(
+        &self,
+        metadata: &PoolMetadata,
+        block_number: u64,
+    ) -> Result<PoolSnapshot> {
+        let result = self
+            .rpc
+            .multicall(
+                &[
+                    Call3::function(self.pool_address, "getReserves()", &[]),
+                    Call3::function(
+                        metadata.factory,
+                        "getFee(address,bool)",
+                        &[
+                            Token::Address(self.pool_address),
+                            Token::Bool(metadata.curve.stable),
+                        ],
+                    ),
+                ],
+                Some(block_number),
+                "getReserves+getFee",
+            )
+            .await?;
+
+        let get_reserves = result.output(0, "getReserves", 64)?;
+        let reserve0 = U256::from_big_endian(&get_reserves[0..32]);
+        let reserve1 = U256::from_big_endian(&get_reserves[32..64]);
+
+        if reserve0.is_zero() || reserve1.is_zero() {
             return Err(ArgusError::ContractError(
-                "Invalid reserves response - insufficient data".to_string(),
+                "Pool has no liquidity".to_string(),
             ));
         }
 
-        let reserve0 = U256::from_big_endian(&result[0..32]);
-        let reserve1 = U256::from_big_endian(&result[32..64]);
+        let fee = U256::from_big_endian(&result.output(1, "getFee", 32)?[0..32]);
+        if fee >= U256::from(math::FEE_DENOMINATOR) {
+            return Err(ArgusError::ContractError(format!(
+                "Implausible Aerodrome fee: {fee} bps"
+            )));
+        }
+
+        Ok(PoolSnapshot {
+            reserve0,
+            reserve1,
+            fee_bps: fee.as_u64(),
+        })
+    }
+
+    fn decimals(scale: U256) -> u32 {
+        let mut decimals = 0;
+        let mut scale = scale;
+        while scale > U256::one() {
+            scale /= 10;
+            decimals += 1;
+        }
+        decimals
+    }
+
+    /// Marginal token1-per-token0 price in human units for the pool's curve.
+    fn spot_price(reserve0: U256, reserve1: U256, curve: &CurveParams) -> Result<f64> {
+        let human = |reserve: U256, decimals: u32| -> Result<f64> {
+            crate::utils::raw_to_decimal(reserve, decimals)?
+                .to_string()
+                .parse::<f64>()
+                .map_err(|e| ArgusError::CalculationError(format!("Reserve conversion: {e}")))
+        };
+
+        Ok(math::spot_price(
+            human(reserve0, Self::decimals(curve.decimals0))?,
+            human(reserve1, Self::decimals(curve.decimals1))?,
+            curve.stable,
+        ))
+    }
+}
+
+#[async_trait]
+impl EventSourcedPool for AerodromeClient {
+    type State = PoolSnapshot;
+
+    fn name(&self) -> &'static str {
+        "Aerodrome"
+    }
+
+    fn rpc(&self) -> &Arc<RpcClient> {
+        &self.rpc
+    }
+
+    fn cache(&self) -> &PoolStateCache<PoolSnapshot> {
+        &self.cache
+    }
+
+    fn event_filter(&self) -> Filter {
+        Filter::new()
+            .address(self.pool_address)
+            .topic0(H256::from(keccak256(SYNC_EVENT)))
+    }
+
+    async fn read_state(&self, block_number: u64) -> Result<PoolSnapshot> {
+        let metadata = self.get_metadata().await?;
+        self.read_snapshot(&metadata, block_number).await
+    }
+
+    fn apply_event(&self, state: &mut PoolSnapshot, log: &Log) -> Result<()> {
+        if log.data.len() < 64 {
+            return Err(ArgusError::ContractError(format!(
+                "Truncated Sync event in tx {:?}",
+                log.transaction_hash
+            )));
+        }
 
-        let r0 = reserve0.as_u128();
-        let r1 = reserve1.as_u128();
+        state.reserve0 = U256::from_big_endian(&log.data[0..32]);
+        state.reserve1 = U256::from_big_endian(&log.data[32..64]);
 
-        if r0 == 0 || r1 == 0 {
+        if state.reserve0.is_zero() || state.reserve1.is_zero() {
             return Err(ArgusError::ContractError(
                 "Pool has no liquidity".to_string(),
             ));
         }
-
-        Ok((r0, r1))
+        Ok(())
     }
 }
 
 #[async_trait]
 impl DexClient for AerodromeClient {
-    async fn get_pool_state(&self) -> Result<PoolState> {
-        let (reserve0, reserve1) = self.get_reserves().await?;
+    async fn get_pool_state(&self, block_number: u64) -> Result<PoolState> {
+        let metadata = self.get_metadata().await?;
+        let PoolSnapshot {
+            reserve0,
+            reserve1,
+            fee_bps,
+        } = pool_cache::state_at(self, block_number).await?;
+
+        let spot_price = Self::spot_price(reserve0, reserve1, &metadata.curve)?;
+        let decimals_diff = i32::try_from(Self::decimals(metadata.curve.decimals1)).unwrap_or(0)
+            - i32::try_from(Self::decimals(metadata.curve.decimals0)).unwrap_or(0);
 
         #[allow(
             clippy::cast_precision_loss,
@@ -75,13 +244,20 @@ impl DexClient for AerodromeClient {
             clippy::cast_sign_loss
         )]
         let sqrt_price =
-            ((reserve1 as f64 / reserve0 as f64).sqrt() * (1u128 << 96) as f64) as u128;
+            ((spot_price * 10f64.powi(decimals_diff)).sqrt() * (1u128 << 96) as f64) as u128;
+
+        let liquidity = reserve0
+            .saturating_mul(reserve1)
+            .integer_sqrt()
+            .min(U256::from(u128::MAX))
+            .as_u128();
 
         Ok(PoolState {
             sqrt_price_x96: sqrt_price,
             tick: 0,
-            liquidity: reserve0.saturating_add(reserve1) / 2,
-            fee: 30,
+            liquidity,
+            // PoolState fees are in hundredths of a bip
+            fee: u32::try_from(fee_bps * 100).unwrap_or(u32::MAX),
         })
     }
 
@@ -89,48 +265,75 @@ impl DexClient for AerodromeClient {
         &self,
         amount_in: Decimal,
         zero_for_one: bool,
+        block_number: u64,
     ) -> Result<SwapQuote> {
-        let (reserve0, reserve1) = self.get_reserves().await?;
-
-        let amount_in_wei = (amount_in
-            * Decimal::from_str("1000000000000000000").map_err(|e| {
-                ArgusError::CalculationError(format!("Decimal conversion error: {e}"))
-            })?)
-        .round_dp(0)
-        .to_string()
-        .parse::<u128>()
-        .map_err(|e| ArgusError::CalculationError(format!("Failed to parse amount: {e}")))?;
-
-        let (reserve_in, reserve_out) = if zero_for_one {
-            (reserve0, reserve1)
+        if amount_in <= Decimal::ZERO {
+            tracing::error!(
+                "Attempted to calculate Aerodrome effective price with zero input amount"
+            );
+            return Err(crate::models::ArgusError::CalculationError(
+                "Cannot calculate effective price with zero input amount".to_string(),
+            ));
+        }
+
+        let metadata = self.get_metadata().await?;
+        let PoolSnapshot {
+            reserve0,
+            reserve1,
+            fee_bps,
+        } = pool_cache::state_at(self, block_number).await?;
+        let curve = metadata.curve;
+
+        let (decimals_in, decimals_out) = if zero_for_one {
+            (
+                Self::decimals(curve.decimals0),
+                Self::decimals(curve.decimals1),
+            )
         } else {
-            (reserve1, reserve0)
+            (
+                Self::decimals(curve.decimals1),
+                Self::decimals(curve.decimals0),
+            )
         };
 
-        let amount_out = Self::get_amount_out(amount_in_wei, reserve_in, reserve_out)?;
+        let amount_in_raw = crate::utils::decimal_to_raw(amount_in, decimals_in)?;
 
-        let amount_out_decimal = Decimal::from(amount_out)
-            / Decimal::from_str("1000000").map_err(|e| {
-                ArgusError::CalculationError(format!("Decimal conversion error: {e}"))
-            })?;
+        let amount_out = math::get_amount_out(
+            amount_in_raw,
+            zero_for_one,
+            reserve0,
+            reserve1,
+            fee_bps,
+            curve,
+        )?;
+
+        let amount_out_decimal = crate::utils::raw_to_decimal(amount_out, decimals_out)?;
 
-        let spot_price = Decimal::from(reserve1) / Decimal::from(reserve0)
-            * Decimal::from_str("1000000000000").map_err(|e| {
-                ArgusError::CalculationError(format!("Decimal conversion error: {e}"))
+        let spot_price =
+            Decimal::try_from(Self::spot_price(reserve0, reserve1, &curve)?).map_err(|e| {
+                ArgusError::CalculationError(format!("Failed to convert spot price: {e}"))
             })?;
 
-        let effective_price = if amount_in > Decimal::ZERO {
-            amount_out_decimal / amount_in
+        let (effective_price, price_impact) = if zero_for_one {
+            (
+                amount_out_decimal / amount_in,
+                crate::utils::calculate_price_impact(amount_in, amount_out_decimal, spot_price),
+            )
         } else {
-            tracing::error!(
-                "Attempted to calculate Aerodrome effective price with zero input amount"
-            );
-            return Err(crate::models::ArgusError::CalculationError(
-                "Cannot calculate effective price with zero input amount".to_string(),
-            ));
+            if amount_out_decimal.is_zero() {
+                return Err(ArgusError::CalculationError(
+                    "Aerodrome swap returned zero output".to_string(),
+                ));
+            }
+            (
+                amount_in / amount_out_decimal,
+                crate::utils::calculate_price_impact(
+                    amount_in,
+                    amount_out_decimal,
+                    Decimal::ONE / spot_price,
+                ),
+            )
         };
-        let price_impact =
-            crate::utils::calculate_price_impact(amount_in, amount_out_decimal, spot_price);
 
         Ok(SwapQuote {
             amount_out: amount_out_decimal,
@@ -143,25 +346,61 @@ impl DexClient for AerodromeClient {
     async fn estimate_gas(&self) -> Result<u64> {
         Ok(80000)
     }
-}
 
-impl AerodromeClient {
-    fn get_amount_out(amount_in: u128, reserve_in: u128, reserve_out: u128) -> Result<u128> {
-        if reserve_in == 0 || reserve_out == 0 {
-            return Err(ArgusError::CalculationError(
-                "Insufficient liquidity".to_string(),
-            ));
-        }
+    async fn build_swap_calldata(&self, params: &SwapCalldataParams) -> Result<SwapCalldata> {
+        let metadata = self.get_metadata().await?;
+
+        let (token_in, token_out, decimals_in, decimals_out) = if params.zero_for_one {
+            (
+                metadata.token0,
+                metadata.token1,
+                metadata.curve.decimals0,
+                metadata.curve.decimals1,
+            )
+        } else {
+            (
+                metadata.token1,
+                metadata.token0,
+                metadata.curve.decimals1,
+                metadata.curve.decimals0,
+            )
+        };
 
-        let amount_in_with_fee = amount_in * 9999 / 10000;
+        let amount_in =
+            crate::utils::decimal_to_raw(params.amount_in, Self::decimals(decimals_in))?;
+        let min_amount_out =
+            crate::utils::decimal_to_raw(params.min_amount_out, Self::decimals(decimals_out))?;
 
-        let numerator = amount_in_with_fee * reserve_out;
-        let denominator = reserve_in + amount_in_with_fee;
+        let to = Address::from_str(super::ROUTER_ADDRESS)
+            .map_err(|e| ArgusError::ContractError(format!("Invalid router address: {e}")))?;
 
-        if denominator == 0 {
-            return Err(ArgusError::CalculationError("Division by zero".to_string()));
-        }
+        let mut state_overrides = spoof::state();
+        state_override::fund_native(&mut state_overrides, params.recipient, U256::zero());
+        state_override::fund_erc20(
+            &mut state_overrides,
+            token_in,
+            params.recipient,
+            to,
+            amount_in,
+        )?;
 
-        Ok(numerator / denominator)
+        Ok(SwapCalldata {
+            from: params.recipient,
+            to,
+            data: router::encode_swap_exact_tokens_for_tokens(
+                amount_in,
+                min_amount_out,
+                &Route {
+                    from: token_in,
+                    to: token_out,
+                    stable: metadata.curve.stable,
+                    factory: metadata.factory,
+                },
+                params.recipient,
+                params.deadline,
+            ),
+            value: U256::zero(),
+            state_overrides,
+        })
     }
 }
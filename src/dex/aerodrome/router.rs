@@ -0,0 +1,77 @@
+/*
+ * Aerodrome Router and Slipstream SwapRouter calldata encoding
+ */
+
+use ethers::{
+    abi::{encode, Token},
+    types::{Address, I256, U256},
+    utils::keccak256,
+};
+
+/// One hop of an Aerodrome Router path; the pool is looked up by `(from, to, stable, factory)`.
+#[derive(Debug, Clone, Copy)]
+pub struct Route {
+    pub from: Address,
+    pub to: Address,
+    pub stable: bool,
+    pub factory: Address,
+}
+
+/// Encodes `Router.swapExactTokensForTokens(amountIn, amountOutMin, routes, to, deadline)`
+/// with a single-hop route.
+#[must_use]
+pub fn encode_swap_exact_tokens_for_tokens(
+    amount_in: U256,
+    min_amount_out: U256,
+    route: &Route,
+    recipient: Address,
+    deadline: u64,
+) -> Vec<u8> {
+    let route = Token::Tuple(vec![
+        Token::Address(route.from),
+        Token::Address(route.to),
+        Token::Bool(route.stable),
+        Token::Address(route.factory),
+    ]);
+
+    let mut calldata = keccak256(
+        b"swapExactTokensForTokens(uint256,uint256,(address,address,bool,address)[],address,uint256)",
+    )[0..4]
+        .to_vec();
+    calldata.extend_from_slice(&encode(&[
+        Token::Uint(amount_in),
+        Token::Uint(min_amount_out),
+        Token::Array(vec![route]),
+        Token::Address(recipient),
+        Token::Uint(deadline.into()),
+    ]));
+    calldata
+}
+
+/// Encodes Slipstream `SwapRouter.exactInputSingle(params)` without a price limit.
+#[must_use]
+pub fn encode_exact_input_single(
+    token_in: Address,
+    token_out: Address,
+    tick_spacing: i32,
+    amount_in: U256,
+    min_amount_out: U256,
+    recipient: Address,
+    deadline: u64,
+) -> Vec<u8> {
+    let mut calldata = keccak256(
+        b"exactInputSingle((address,address,int24,address,uint256,uint256,uint256,uint160))",
+    )[0..4]
+        .to_vec();
+    calldata.extend_from_slice(&encode(&[Token::Tuple(vec![
+        Token::Address(token_in),
+        Token::Address(token_out),
+        Token::Int(I256::from(tick_spacing).into_raw()),
+        Token::Address(recipient),
+        Token::Uint(deadline.into()),
+        Token::Uint(amount_in),
+        Token::Uint(min_amount_out),
+        Token::Uint(U256::zero()),
+    ])]));
+    calldata
+}
@@ -0,0 +1,348 @@
+/*
+ * Aerodrome Slipstream (concentrated liquidity) pool client implementation
+ */
+
+use crate::dex::clmm::sqrt_price_math::sqrt_price_to_u128;
+use crate::dex::clmm::{self, SwapStartState, TickDataProvider};
+use crate::dex::{
+    state_override, DexClient, PoolState, SwapCalldata, SwapCalldataParams, SwapQuote,
+};
+use crate::models::{ArgusError, Result};
+use crate::rpc::{Call3, RpcClient};
+use async_trait::async_trait;
+use ethers::{
+    abi::Token,
+    types::{spoof, Address, I256, U256},
+};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+
+pub struct SlipstreamClient {
+    rpc: Arc<RpcClient>,
+    pool_address: Address,
+    metadata: OnceCell<PoolMetadata>,
+}
+
+/// Immutable pool properties, read once and cached.
+#[derive(Debug, Clone, Copy)]
+struct PoolMetadata {
+    tick_spacing: i32,
+    token0: Address,
+    token1: Address,
+    decimals0: u32,
+    decimals1: u32,
+}
+
+impl SlipstreamClient {
+    pub fn new(rpc: Arc<RpcClient>) -> Result<Self> {
+        let pool_address = Address::from_str(super::SLIPSTREAM_POOL_ADDRESS).map_err(|e| {
+            ArgusError::ContractError(format!("Invalid Slipstream pool address: {e}"))
+        })?;
+
+        Ok(Self::with_pool(rpc, pool_address))
+    }
+
+    #[must_use]
+    pub fn with_pool(rpc: Arc<RpcClient>, pool_address: Address) -> Self {
+        Self {
+            rpc,
+            pool_address,
+            metadata: OnceCell::new(),
+        }
+    }
+
+    async fn get_metadata(&self) -> Result<PoolMetadata> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let pool = self
+                    .rpc
+                    .multicall(
+                        &[
+                            Call3::function(self.pool_address, "tickSpacing()", &[]),
+                            Call3::function(self.pool_address, "token0()", &[]),
+                            Call3::function(self.pool_address, "token1()", &[]),
+                        ],
+                        None,
+                        "tickSpacing+token0+token1",
+                    )
+                    .await?;
+
+                let tick_spacing = pool.output(0, "tickSpacing", 32)?;
+                let token0 = Address::from_slice(&pool.output(1, "token0", 32)?[12..32]);
+                let token1 = Address::from_slice(&pool.output(2, "token1", 32)?[12..32]);
+
+                let decimals = self
+                    .rpc
+                    .multicall(
+                        &[
+                            Call3::function(token0, "decimals()", &[]),
+                            Call3::function(token1, "decimals()", &[]),
+                        ],
+                        Some(pool.block_number),
+                        "decimals",
+                    )
+                    .await?;
+
+                Ok(PoolMetadata {
+                    tick_spacing: I256::from_raw(U256::from_big_endian(&tick_spacing[0..32]))
+                        .as_i32(),
+                    token0,
+                    token1,
+                    decimals0: U256::from_big_endian(&decimals.output(0, "decimals", 32)?[0..32])
+                        .as_u32(),
+                    decimals1: U256::from_big_endian(&decimals.output(1, "decimals", 32)?[0..32])
+                        .as_u32(),
+                })
+            })
+            .await
+            .copied()
+    }
+
+    /// Reads everything a swap simulation needs in one batch at `block_number`.
+    async fn read_swap_start(&self, block_number: u64) -> Result<(SwapStartState, PoolMetadata)> {
+        let metadata = self.get_metadata().await?;
+        let result = self
+            .rpc
+            .multicall(
+                &[
+                    Call3::function(self.pool_address, "slot0()", &[]),
+                    Call3::function(self.pool_address, "liquidity()", &[]),
+                    Call3::function(self.pool_address, "fee()", &[]),
+                ],
+                Some(block_number),
+                "slot0+liquidity+fee",
+            )
+            .await?;
+
+        let slot0 = result.output(0, "slot0", 64)?;
+        let liquidity = result.output(1, "liquidity", 32)?;
+        let fee = result.output(2, "fee", 32)?;
+
+        Ok((
+            SwapStartState {
+                block_number: result.block_number,
+                sqrt_price_x96: U256::from_big_endian(&slot0[0..32]),
+                tick: I256::from_raw(U256::from_big_endian(&slot0[32..64])).as_i32(),
+                liquidity: U256::from_big_endian(&liquidity[0..32]).as_u128(),
+                tick_spacing: metadata.tick_spacing,
+                fee_pips: U256::from_big_endian(&fee[0..32]).as_u32(),
+            },
+            metadata,
+        ))
+    }
+
+    /// Token1-per-token0 price in human units.
+    fn spot_price(sqrt_price_x96: U256, metadata: &PoolMetadata) -> Result<Decimal> {
+        let sqrt_price = sqrt_price_x96
+            .to_string()
+            .parse::<f64>()
+            .map_err(|e| ArgusError::CalculationError(format!("Sqrt price conversion: {e}")))?
+            / 2f64.powi(96);
+
+        let decimals_diff = i32::try_from(metadata.decimals0).unwrap_or(0)
+            - i32::try_from(metadata.decimals1).unwrap_or(0);
+
+        Decimal::try_from(sqrt_price * sqrt_price * 10f64.powi(decimals_diff))
+            .map_err(|e| ArgusError::CalculationError(format!("Failed to convert spot price: {e}")))
+    }
+}
+
+#[async_trait]
+impl TickDataProvider for SlipstreamClient {
+    async fn get_tick_bitmap_words(
+        &self,
+        word_positions: &[i16],
+        block_number: u64,
+    ) -> Result<Vec<U256>> {
+        let calls: Vec<Call3> = word_positions
+            .iter()
+            .map(|word_pos| {
+                Call3::function(
+                    self.pool_address,
+                    "tickBitmap(int16)",
+                    &[Token::Int(I256::from(*word_pos).into_raw())],
+                )
+            })
+            .collect();
+
+        let result = self
+            .rpc
+            .multicall(&calls, Some(block_number), "tickBitmap")
+            .await?;
+
+        (0..calls.len())
+            .map(|index| {
+                let word = result.output(index, "tickBitmap", 32)?;
+                Ok(U256::from_big_endian(&word[0..32]))
+            })
+            .collect()
+    }
+
+    async fn get_liquidity_nets(&self, ticks: &[i32], block_number: u64) -> Result<Vec<i128>> {
+        let calls: Vec<Call3> = ticks
+            .iter()
+            .map(|tick| {
+                Call3::function(
+                    self.pool_address,
+                    "ticks(int24)",
+                    &[Token::Int(I256::from(*tick).into_raw())],
+                )
+            })
+            .collect();
+
+        let result = self
+            .rpc
+            .multicall(&calls, Some(block_number), "ticks")
+            .await?;
+
+        (0..calls.len())
+            .map(|index| {
+                let tick = result.output(index, "ticks", 64)?;
+                Ok(I256::from_raw(U256::from_big_endian(&tick[32..64])).as_i128())
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl DexClient for SlipstreamClient {
+    async fn get_pool_state(&self, block_number: u64) -> Result<PoolState> {
+        let (start, _) = self.read_swap_start(block_number).await?;
+
+        Ok(PoolState {
+            sqrt_price_x96: sqrt_price_to_u128(start.sqrt_price_x96)?,
+            tick: start.tick,
+            liquidity: start.liquidity,
+            fee: start.fee_pips,
+        })
+    }
+
+    async fn calculate_swap_output(
+        &self,
+        amount_in: Decimal,
+        zero_for_one: bool,
+        block_number: u64,
+    ) -> Result<SwapQuote> {
+        if amount_in <= Decimal::ZERO {
+            tracing::error!("Attempted to calculate Slipstream swap with non-positive input");
+            return Err(ArgusError::CalculationError(
+                "Cannot simulate swap with non-positive input amount".to_string(),
+            ));
+        }
+
+        let (start, metadata) = self.read_swap_start(block_number).await?;
+
+        let (decimals_in, decimals_out) = if zero_for_one {
+            (metadata.decimals0, metadata.decimals1)
+        } else {
+            (metadata.decimals1, metadata.decimals0)
+        };
+
+        let simulation = clmm::simulate_exact_input(
+            self,
+            start,
+            crate::utils::decimal_to_raw(amount_in, decimals_in)?,
+            zero_for_one,
+        )
+        .await?;
+
+        if simulation.amount_out.is_zero() {
+            return Err(ArgusError::CalculationError(
+                "Slipstream swap simulation returned zero output".to_string(),
+            ));
+        }
+
+        let amount_in_used = crate::utils::raw_to_decimal(simulation.amount_in, decimals_in)?;
+        let amount_out = crate::utils::raw_to_decimal(simulation.amount_out, decimals_out)?;
+
+        tracing::debug!(
+            "Slipstream swap simulation: ticks_crossed={}, tick {} -> {}",
+            simulation.ticks_crossed,
+            start.tick,
+            simulation.tick_after
+        );
+
+        let spot_price = Self::spot_price(start.sqrt_price_x96, &metadata)?;
+
+        let (effective_price, price_impact) = if zero_for_one {
+            (
+                amount_out / amount_in_used,
+                crate::utils::calculate_price_impact(amount_in_used, amount_out, spot_price),
+            )
+        } else {
+            (
+                amount_in_used / amount_out,
+                crate::utils::calculate_price_impact(
+                    amount_in_used,
+                    amount_out,
+                    Decimal::ONE / spot_price,
+                ),
+            )
+        };
+
+        Ok(SwapQuote {
+            amount_out,
+            effective_price,
+            price_impact,
+            gas_estimate: 120_000,
+        })
+    }
+
+    async fn estimate_gas(&self) -> Result<u64> {
+        Ok(120_000)
+    }
+
+    async fn build_swap_calldata(&self, params: &SwapCalldataParams) -> Result<SwapCalldata> {
+        let metadata = self.get_metadata().await?;
+
+        let (token_in, token_out, decimals_in, decimals_out) = if params.zero_for_one {
+            (
+                metadata.token0,
+                metadata.token1,
+                metadata.decimals0,
+                metadata.decimals1,
+            )
+        } else {
+            (
+                metadata.token1,
+                metadata.token0,
+                metadata.decimals1,
+                metadata.decimals0,
+            )
+        };
+
+        let to = Address::from_str(super::SLIPSTREAM_ROUTER_ADDRESS).map_err(|e| {
+            ArgusError::ContractError(format!("Invalid Slipstream router address: {e}"))
+        })?;
+
+        let amount_in = crate::utils::decimal_to_raw(params.amount_in, decimals_in)?;
+
+        let mut state_overrides = spoof::state();
+        state_override::fund_native(&mut state_overrides, params.recipient, U256::zero());
+        state_override::fund_erc20(
+            &mut state_overrides,
+            token_in,
+            params.recipient,
+            to,
+            amount_in,
+        )?;
+
+        Ok(SwapCalldata {
+            from: params.recipient,
+            to,
+            data: super::router::encode_exact_input_single(
+                token_in,
+                token_out,
+                metadata.tick_spacing,
+                amount_in,
+                crate::utils::decimal_to_raw(params.min_amount_out, decimals_out)?,
+                params.recipient,
+                params.deadline,
+            ),
+            value: U256::zero(),
+            state_overrides,
+        })
+    }
+}
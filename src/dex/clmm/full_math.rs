@@ -0,0 +1,50 @@
+/*
+ * 512-bit intermediate multiplication and division (FullMath port)
+ */
+
+use crate::models::{ArgusError, Result};
+use ethers::types::{U256, U512};
+
+/// Computes `floor(a * b / denominator)` without overflowing the intermediate product.
+pub fn mul_div(a: U256, b: U256, denominator: U256) -> Result<U256> {
+    if denominator.is_zero() {
+        return Err(ArgusError::CalculationError(
+            "mul_div: division by zero".to_string(),
+        ));
+    }
+
+    let result = a.full_mul(b) / U512::from(denominator);
+    U256::try_from(result)
+        .map_err(|_| ArgusError::CalculationError("mul_div: result overflows U256".to_string()))
+}
+
+/// Computes `ceil(a * b / denominator)` without overflowing the intermediate product.
+pub fn mul_div_rounding_up(a: U256, b: U256, denominator: U256) -> Result<U256> {
+    let result = mul_div(a, b, denominator)?;
+
+    if (a.full_mul(b) % U512::from(denominator)).is_zero() {
+        Ok(result)
+    } else if result == U256::MAX {
+        Err(ArgusError::CalculationError(
+            "mul_div_rounding_up: result overflows U256".to_string(),
+        ))
+    } else {
+        Ok(result + 1)
+    }
+}
+
+/// Computes `ceil(x / y)`, matching `UnsafeMath.divRoundingUp`.
+pub fn div_rounding_up(x: U256, y: U256) -> Result<U256> {
+    if y.is_zero() {
+        return Err(ArgusError::CalculationError(
+            "div_rounding_up: division by zero".to_string(),
+        ));
+    }
+
+    let quotient = x / y;
+    if (x % y).is_zero() {
+        Ok(quotient)
+    } else {
+        Ok(quotient + 1)
+    }
+}
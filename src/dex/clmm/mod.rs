@@ -0,0 +1,266 @@
+/*
+ * Concentrated liquidity math shared by tick-based pools
+ */
+
+pub mod full_math;
+pub mod sqrt_price_math;
+pub mod swap_math;
+pub mod tick_bitmap;
+pub mod tick_math;
+
+use crate::models::{ArgusError, Result};
+use async_trait::async_trait;
+use ethers::types::U256;
+use std::collections::HashMap;
+
+// Bitmap words read up front in the swap direction, along with the liquidity of every
+// initialized tick in them
+const PREFETCH_WORDS: i16 = 2;
+
+// Bitmap words one simulation may read, prefetched ones included. A swap that drains the
+// pool's liquidity would otherwise read every empty word up to MIN_TICK or MAX_TICK.
+const MAX_WORDS_READ: usize = 32;
+
+/// Source of on-chain tick data for a single pool. Each method reads its whole batch at
+/// `block_number` and returns the values in request order.
+#[async_trait]
+pub trait TickDataProvider: Send + Sync {
+    async fn get_tick_bitmap_words(
+        &self,
+        word_positions: &[i16],
+        block_number: u64,
+    ) -> Result<Vec<U256>>;
+    async fn get_liquidity_nets(&self, ticks: &[i32], block_number: u64) -> Result<Vec<i128>>;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SwapStartState {
+    /// Block the state was read at; tick data is read at the same block
+    pub block_number: u64,
+    pub sqrt_price_x96: U256,
+    pub tick: i32,
+    pub liquidity: u128,
+    pub tick_spacing: i32,
+    pub fee_pips: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct SwapSimulation {
+    pub amount_in: U256,
+    pub amount_out: U256,
+    pub fee_paid: U256,
+    pub sqrt_price_x96_after: U256,
+    pub tick_after: i32,
+    pub liquidity_after: u128,
+    pub ticks_crossed: u32,
+}
+
+/// Tick data read so far during one simulation, all at the start state's block.
+struct TickCache<'a> {
+    provider: &'a dyn TickDataProvider,
+    block_number: u64,
+    words: HashMap<i16, U256>,
+    liquidity_nets: HashMap<i32, i128>,
+}
+
+impl<'a> TickCache<'a> {
+    /// Reads the first `PREFETCH_WORDS` bitmap words in the swap direction in one batch,
+    /// then the liquidity of their initialized ticks ahead of the price in a second one.
+    async fn prefetch(
+        provider: &'a dyn TickDataProvider,
+        start: &SwapStartState,
+        zero_for_one: bool,
+    ) -> Result<TickCache<'a>> {
+        let first_word = tick_bitmap::word_for_tick(start.tick, start.tick_spacing, zero_for_one);
+        let word_positions: Vec<i16> = (0..PREFETCH_WORDS)
+            .filter_map(|offset| {
+                if zero_for_one {
+                    first_word.checked_sub(offset)
+                } else {
+                    first_word.checked_add(offset)
+                }
+            })
+            .collect();
+
+        let words = provider
+            .get_tick_bitmap_words(&word_positions, start.block_number)
+            .await?;
+
+        let ticks: Vec<i32> = word_positions
+            .iter()
+            .zip(&words)
+            .flat_map(|(word_pos, word)| {
+                tick_bitmap::initialized_ticks(*word_pos, *word, start.tick_spacing)
+            })
+            .filter(|tick| {
+                if zero_for_one {
+                    *tick <= start.tick
+                } else {
+                    *tick > start.tick
+                }
+            })
+            .collect();
+
+        let liquidity_nets = if ticks.is_empty() {
+            Vec::new()
+        } else {
+            provider
+                .get_liquidity_nets(&ticks, start.block_number)
+                .await?
+        };
+
+        Ok(Self {
+            provider,
+            block_number: start.block_number,
+            words: word_positions.into_iter().zip(words).collect(),
+            liquidity_nets: ticks.into_iter().zip(liquidity_nets).collect(),
+        })
+    }
+
+    async fn word(&mut self, word_pos: i16) -> Result<U256> {
+        if let Some(word) = self.words.get(&word_pos) {
+            return Ok(*word);
+        }
+
+        if self.words.len() >= MAX_WORDS_READ {
+            return Err(ArgusError::CalculationError(format!(
+                "Swap crosses more than {MAX_WORDS_READ} tick bitmap words; \
+                 the pool's liquidity cannot fill it"
+            )));
+        }
+
+        let word = Self::single(
+            self.provider
+                .get_tick_bitmap_words(&[word_pos], self.block_number)
+                .await?,
+        )?;
+        self.words.insert(word_pos, word);
+        Ok(word)
+    }
+
+    async fn liquidity_net(&mut self, tick: i32) -> Result<i128> {
+        if let Some(liquidity_net) = self.liquidity_nets.get(&tick) {
+            return Ok(*liquidity_net);
+        }
+
+        let liquidity_net = Self::single(
+            self.provider
+                .get_liquidity_nets(&[tick], self.block_number)
+                .await?,
+        )?;
+        self.liquidity_nets.insert(tick, liquidity_net);
+        Ok(liquidity_net)
+    }
+
+    fn single<T>(values: Vec<T>) -> Result<T> {
+        values.into_iter().next().ok_or_else(|| {
+            ArgusError::ContractError("Tick data provider returned no value".to_string())
+        })
+    }
+}
+
+/// Walks initialized ticks exactly as the pool's `swap` loop does for an exact-input swap,
+/// stopping once `amount_in` is consumed or the price limit is reached. All tick data is
+/// read at `start.block_number`; fails rather than read more than `MAX_WORDS_READ`
+/// bitmap words.
+pub async fn simulate_exact_input(
+    provider: &dyn TickDataProvider,
+    start: SwapStartState,
+    amount_in: U256,
+    zero_for_one: bool,
+) -> Result<SwapSimulation> {
+    let sqrt_price_limit_x96 = if zero_for_one {
+        tick_math::MIN_SQRT_PRICE + 1
+    } else {
+        tick_math::MAX_SQRT_PRICE - 1
+    };
+
+    let mut ticks = TickCache::prefetch(provider, &start, zero_for_one).await?;
+    let mut amount_remaining = amount_in;
+    let mut amount_out = U256::zero();
+    let mut fee_paid = U256::zero();
+    let mut sqrt_price_x96 = start.sqrt_price_x96;
+    let mut tick = start.tick;
+    let mut liquidity = start.liquidity;
+    let mut ticks_crossed = 0u32;
+
+    while !amount_remaining.is_zero() && sqrt_price_x96 != sqrt_price_limit_x96 {
+        let sqrt_price_start_x96 = sqrt_price_x96;
+
+        let word_pos = tick_bitmap::word_for_tick(tick, start.tick_spacing, zero_for_one);
+        let word = ticks.word(word_pos).await?;
+
+        let (tick_next, initialized) = tick_bitmap::next_initialized_tick_within_one_word(
+            word,
+            tick,
+            start.tick_spacing,
+            zero_for_one,
+        );
+        let tick_next = tick_next.clamp(tick_math::MIN_TICK, tick_math::MAX_TICK);
+
+        let sqrt_price_next_x96 = tick_math::get_sqrt_price_at_tick(tick_next)?;
+        let sqrt_price_target_x96 = if zero_for_one {
+            sqrt_price_next_x96.max(sqrt_price_limit_x96)
+        } else {
+            sqrt_price_next_x96.min(sqrt_price_limit_x96)
+        };
+
+        let step = swap_math::compute_swap_step(
+            sqrt_price_x96,
+            sqrt_price_target_x96,
+            liquidity,
+            amount_remaining,
+            start.fee_pips,
+        )?;
+
+        sqrt_price_x96 = step.sqrt_price_next_x96;
+        amount_remaining -= step.amount_in + step.fee_amount;
+        amount_out += step.amount_out;
+        fee_paid += step.fee_amount;
+
+        if sqrt_price_x96 == sqrt_price_next_x96 {
+            if initialized {
+                let liquidity_net = ticks.liquidity_net(tick_next).await?;
+                let liquidity_net = if zero_for_one {
+                    -liquidity_net
+                } else {
+                    liquidity_net
+                };
+                liquidity = add_delta(liquidity, liquidity_net)?;
+                ticks_crossed += 1;
+            }
+            tick = if zero_for_one {
+                tick_next - 1
+            } else {
+                tick_next
+            };
+        } else if sqrt_price_x96 != sqrt_price_start_x96 {
+            tick = tick_math::get_tick_at_sqrt_price(sqrt_price_x96)?;
+        }
+    }
+
+    Ok(SwapSimulation {
+        amount_in: amount_in - amount_remaining,
+        amount_out,
+        fee_paid,
+        sqrt_price_x96_after: sqrt_price_x96,
+        tick_after: tick,
+        liquidity_after: liquidity,
+        ticks_crossed,
+    })
+}
+
+/// Applies a signed liquidity delta, failing on underflow or overflow.
+pub fn add_delta(liquidity: u128, delta: i128) -> Result<u128> {
+    let result = if delta < 0 {
+        liquidity.checked_sub(delta.unsigned_abs())
+    } else {
+        liquidity.checked_add(delta.unsigned_abs())
+    };
+
+    result.ok_or_else(|| {
+        ArgusError::CalculationError(format!(
+            "Liquidity delta {delta} out of bounds for liquidity {liquidity}"
+        ))
+    })
+}
@@ -0,0 +1,141 @@
+/*
+ * Price movement and token delta helpers (SqrtPriceMath port)
+ */
+
+use super::full_math::{div_rounding_up, mul_div, mul_div_rounding_up};
+use crate::models::{ArgusError, Result};
+use ethers::types::U256;
+
+const RESOLUTION: usize = 96;
+
+fn q96() -> U256 {
+    U256::one() << RESOLUTION
+}
+
+fn max_u160() -> U256 {
+    (U256::one() << 160) - 1
+}
+
+/// Next sqrt price after adding `amount` of token0, rounded up so the price never
+/// moves further than the exact result.
+pub fn get_next_sqrt_price_from_amount0_rounding_up(
+    sqrt_price_x96: U256,
+    liquidity: u128,
+    amount: U256,
+) -> Result<U256> {
+    if amount.is_zero() {
+        return Ok(sqrt_price_x96);
+    }
+
+    let numerator1 = U256::from(liquidity) << RESOLUTION;
+
+    let (product, overflowed) = amount.overflowing_mul(sqrt_price_x96);
+    if !overflowed {
+        let denominator = numerator1.overflowing_add(product);
+        if !denominator.1 && denominator.0 >= numerator1 {
+            return mul_div_rounding_up(numerator1, sqrt_price_x96, denominator.0);
+        }
+    }
+
+    div_rounding_up(numerator1, numerator1 / sqrt_price_x96 + amount)
+}
+
+/// Next sqrt price after adding `amount` of token1, rounded down.
+pub fn get_next_sqrt_price_from_amount1_rounding_down(
+    sqrt_price_x96: U256,
+    liquidity: u128,
+    amount: U256,
+) -> Result<U256> {
+    let quotient = if amount <= max_u160() {
+        (amount << RESOLUTION) / U256::from(liquidity)
+    } else {
+        mul_div(amount, q96(), U256::from(liquidity))?
+    };
+
+    let next = sqrt_price_x96 + quotient;
+    if next > max_u160() {
+        return Err(ArgusError::CalculationError(
+            "Sqrt price overflow".to_string(),
+        ));
+    }
+    Ok(next)
+}
+
+/// Next sqrt price given an exact input amount of token0 or token1.
+pub fn get_next_sqrt_price_from_input(
+    sqrt_price_x96: U256,
+    liquidity: u128,
+    amount_in: U256,
+    zero_for_one: bool,
+) -> Result<U256> {
+    if sqrt_price_x96.is_zero() || liquidity == 0 {
+        return Err(ArgusError::CalculationError(
+            "Cannot move price without liquidity".to_string(),
+        ));
+    }
+
+    if zero_for_one {
+        get_next_sqrt_price_from_amount0_rounding_up(sqrt_price_x96, liquidity, amount_in)
+    } else {
+        get_next_sqrt_price_from_amount1_rounding_down(sqrt_price_x96, liquidity, amount_in)
+    }
+}
+
+/// Amount of token0 between two sqrt prices for the given liquidity.
+pub fn get_amount0_delta(
+    sqrt_price_from_x96: U256,
+    sqrt_price_to_x96: U256,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256> {
+    let (lower, upper) = if sqrt_price_from_x96 > sqrt_price_to_x96 {
+        (sqrt_price_to_x96, sqrt_price_from_x96)
+    } else {
+        (sqrt_price_from_x96, sqrt_price_to_x96)
+    };
+
+    if lower.is_zero() {
+        return Err(ArgusError::CalculationError(
+            "Sqrt price must be non-zero".to_string(),
+        ));
+    }
+
+    let numerator1 = U256::from(liquidity) << RESOLUTION;
+    let numerator2 = upper - lower;
+
+    if round_up {
+        div_rounding_up(mul_div_rounding_up(numerator1, numerator2, upper)?, lower)
+    } else {
+        Ok(mul_div(numerator1, numerator2, upper)? / lower)
+    }
+}
+
+/// Amount of token1 between two sqrt prices for the given liquidity.
+pub fn get_amount1_delta(
+    sqrt_price_from_x96: U256,
+    sqrt_price_to_x96: U256,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<U256> {
+    let (lower, upper) = if sqrt_price_from_x96 > sqrt_price_to_x96 {
+        (sqrt_price_to_x96, sqrt_price_from_x96)
+    } else {
+        (sqrt_price_from_x96, sqrt_price_to_x96)
+    };
+
+    if round_up {
+        mul_div_rounding_up(U256::from(liquidity), upper - lower, q96())
+    } else {
+        mul_div(U256::from(liquidity), upper - lower, q96())
+    }
+}
+
+/// Narrows a uint160 sqrt price to the `u128` pool state keeps, failing on prices above
+/// it instead of truncating.
+pub fn sqrt_price_to_u128(sqrt_price_x96: U256) -> Result<u128> {
+    u128::try_from(sqrt_price_x96).map_err(|_| {
+        ArgusError::CalculationError(format!(
+            "Sqrt price {sqrt_price_x96} does not fit in 128 bits"
+        ))
+    })
+}
@@ -0,0 +1,196 @@
+/*
+ * Single swap step within one tick range (SwapMath port, exact input only)
+ */
+
+use super::full_math::{mul_div, mul_div_rounding_up};
+use super::sqrt_price_math::{
+    get_amount0_delta, get_amount1_delta, get_next_sqrt_price_from_input,
+};
+use crate::models::Result;
+use ethers::types::U256;
+
+/// Fees are expressed in hundredths of a bip, so `1_000_000` is a 100% fee.
+pub const MAX_SWAP_FEE: u32 = 1_000_000;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SwapStep {
+    pub sqrt_price_next_x96: U256,
+    pub amount_in: U256,
+    pub amount_out: U256,
+    pub fee_amount: U256,
+}
+
+/// Swaps as much of `amount_remaining` as possible before reaching `sqrt_price_target_x96`.
+pub fn compute_swap_step(
+    sqrt_price_current_x96: U256,
+    sqrt_price_target_x96: U256,
+    liquidity: u128,
+    amount_remaining: U256,
+    fee_pips: u32,
+) -> Result<SwapStep> {
+    let zero_for_one = sqrt_price_current_x96 >= sqrt_price_target_x96;
+    let max_fee = U256::from(MAX_SWAP_FEE);
+    let fee = U256::from(fee_pips);
+
+    let amount_remaining_less_fee = mul_div(amount_remaining, max_fee - fee, max_fee)?;
+
+    let amount_in_to_target = if zero_for_one {
+        get_amount0_delta(
+            sqrt_price_target_x96,
+            sqrt_price_current_x96,
+            liquidity,
+            true,
+        )?
+    } else {
+        get_amount1_delta(
+            sqrt_price_current_x96,
+            sqrt_price_target_x96,
+            liquidity,
+            true,
+        )?
+    };
+
+    let (sqrt_price_next_x96, amount_in, fee_amount) =
+        if amount_remaining_less_fee >= amount_in_to_target {
+            let fee_amount = if fee_pips == MAX_SWAP_FEE {
+                amount_in_to_target
+            } else {
+                mul_div_rounding_up(amount_in_to_target, fee, max_fee - fee)?
+            };
+            (sqrt_price_target_x96, amount_in_to_target, fee_amount)
+        } else {
+            let next = get_next_sqrt_price_from_input(
+                sqrt_price_current_x96,
+                liquidity,
+                amount_remaining_less_fee,
+                zero_for_one,
+            )?;
+            (
+                next,
+                amount_remaining_less_fee,
+                amount_remaining - amount_remaining_less_fee,
+            )
+        };
+
+    let amount_out = if zero_for_one {
+        get_amount1_delta(
+            sqrt_price_next_x96,
+            sqrt_price_current_x96,
+            liquidity,
+            false,
+        )?
+    } else {
+        get_amount0_delta(
+            sqrt_price_current_x96,
+            sqrt_price_next_x96,
+            liquidity,
+            false,
+        )?
+    };
+
+    Ok(SwapStep {
+        sqrt_price_next_x96,
+        amount_in,
+        amount_out,
+        fee_amount,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u256(value: &str) -> U256 {
+        U256::from_dec_str(value).unwrap()
+    }
+
+    // encodePriceSqrt(1, 1)
+    fn price_one() -> U256 {
+        U256::one() << 96
+    }
+
+    #[test]
+    fn exact_input_capped_at_price_target() {
+        let target = u256("79623317895830914510639640423"); // encodePriceSqrt(101, 100)
+        let step = compute_swap_step(
+            price_one(),
+            target,
+            2_000_000_000_000_000_000,
+            u256("1000000000000000000"),
+            600,
+        )
+        .unwrap();
+
+        assert_eq!(step.amount_in, u256("9975124224178055"));
+        assert_eq!(step.fee_amount, u256("5988667735148"));
+        assert_eq!(step.amount_out, u256("9925619580021728"));
+        assert_eq!(step.sqrt_price_next_x96, target);
+    }
+
+    #[test]
+    fn exact_input_fully_spent() {
+        let target = u256("250541448375047931186413801569"); // encodePriceSqrt(1000, 100)
+        let amount = u256("1000000000000000000");
+        let step =
+            compute_swap_step(price_one(), target, 2_000_000_000_000_000_000, amount, 600).unwrap();
+
+        assert_eq!(step.amount_in, u256("999400000000000000"));
+        assert_eq!(step.fee_amount, u256("600000000000000"));
+        assert_eq!(step.amount_out, u256("666399946655997866"));
+        assert!(step.sqrt_price_next_x96 < target);
+        assert_eq!(
+            step.sqrt_price_next_x96,
+            get_next_sqrt_price_from_input(
+                price_one(),
+                2_000_000_000_000_000_000,
+                step.amount_in,
+                false
+            )
+            .unwrap()
+        );
+    }
+
+    // SwapMath v4 keeps the whole input less fee as amount in even when it is too small
+    // to move the price; v3 charged all of it as fee
+    #[test]
+    fn input_too_small_to_move_price() {
+        let step = compute_swap_step(
+            U256::from(2413),
+            u256("79887613182836312"),
+            1_985_041_575_832_132_834_610_021_537_970,
+            U256::from(10),
+            1872,
+        )
+        .unwrap();
+
+        assert_eq!(step.amount_in, U256::from(9));
+        assert_eq!(step.fee_amount, U256::one());
+        assert_eq!(step.amount_out, U256::zero());
+        assert_eq!(step.sqrt_price_next_x96, U256::from(2413));
+    }
+
+    #[test]
+    fn zero_for_one_moves_price_down() {
+        let target = u256("78831026366734652303669917531"); // encodePriceSqrt(99, 100)
+        let step = compute_swap_step(
+            price_one(),
+            target,
+            2_000_000_000_000_000_000,
+            u256("1000000000000000000"),
+            3000,
+        )
+        .unwrap();
+
+        assert_eq!(step.sqrt_price_next_x96, target);
+        assert!(step.amount_out < step.amount_in);
+        assert_eq!(
+            step.fee_amount,
+            mul_div_rounding_up(
+                step.amount_in,
+                U256::from(3000),
+                U256::from(MAX_SWAP_FEE - 3000)
+            )
+            .unwrap()
+        );
+    }
+}
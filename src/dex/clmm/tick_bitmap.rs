@@ -0,0 +1,155 @@
+/*
+ * Tick bitmap word/bit addressing (TickBitmap port)
+ */
+
+use ethers::types::U256;
+
+/// Compresses a tick by its spacing, rounding towards negative infinity.
+#[must_use]
+pub fn compress(tick: i32, tick_spacing: i32) -> i32 {
+    let compressed = tick / tick_spacing;
+    if tick < 0 && tick % tick_spacing != 0 {
+        compressed - 1
+    } else {
+        compressed
+    }
+}
+
+/// Splits a compressed tick into its bitmap word index and bit position.
+#[must_use]
+pub fn position(compressed: i32) -> (i16, u8) {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let position = ((compressed >> 8) as i16, (compressed & 0xff) as u8);
+    position
+}
+
+/// Finds the next initialized tick in the same bitmap word as `tick`, given that word.
+///
+/// Returns the tick and whether it is initialized. When searching left (`lte`), the
+/// current tick itself is included; when searching right it is not.
+#[must_use]
+pub fn next_initialized_tick_within_one_word(
+    word: U256,
+    tick: i32,
+    tick_spacing: i32,
+    lte: bool,
+) -> (i32, bool) {
+    let compressed = compress(tick, tick_spacing);
+
+    if lte {
+        let (_, bit_pos) = position(compressed);
+        let mask = (U256::one() << bit_pos) - 1 + (U256::one() << bit_pos);
+        let masked = word & mask;
+
+        let initialized = !masked.is_zero();
+        let next = if initialized {
+            let msb = i32::try_from(masked.bits() - 1).unwrap_or(0);
+            (compressed - (i32::from(bit_pos) - msb)) * tick_spacing
+        } else {
+            (compressed - i32::from(bit_pos)) * tick_spacing
+        };
+        (next, initialized)
+    } else {
+        let (_, bit_pos) = position(compressed + 1);
+        let mask = !((U256::one() << bit_pos) - 1);
+        let masked = word & mask;
+
+        let initialized = !masked.is_zero();
+        let next = if initialized {
+            let lsb = i32::try_from(masked.trailing_zeros()).unwrap_or(0);
+            (compressed + 1 + (lsb - i32::from(bit_pos))) * tick_spacing
+        } else {
+            (compressed + 1 + (255 - i32::from(bit_pos))) * tick_spacing
+        };
+        (next, initialized)
+    }
+}
+
+/// Word index that `next_initialized_tick_within_one_word` will need for `tick`.
+#[must_use]
+pub fn word_for_tick(tick: i32, tick_spacing: i32, lte: bool) -> i16 {
+    let compressed = compress(tick, tick_spacing);
+    if lte {
+        position(compressed).0
+    } else {
+        position(compressed + 1).0
+    }
+}
+
+/// Initialized ticks marked in bitmap word `word_pos`, in ascending order.
+#[must_use]
+pub fn initialized_ticks(word_pos: i16, word: U256, tick_spacing: i32) -> Vec<i32> {
+    (0..=255u8)
+        .filter(|bit| word.bit(usize::from(*bit)))
+        .map(|bit| (i32::from(word_pos) * 256 + i32::from(bit)) * tick_spacing)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    // Initialized ticks of the TickBitmap test suite, at tick spacing 1
+    const TICKS: [i32; 9] = [-200, -55, -4, 70, 78, 84, 139, 240, 535];
+
+    fn next(ticks: &[i32], tick: i32, lte: bool) -> (i32, bool) {
+        let mut words: HashMap<i16, U256> = HashMap::new();
+        for &initialized in ticks {
+            let (word_pos, bit_pos) = position(compress(initialized, 1));
+            *words.entry(word_pos).or_default() |= U256::one() << bit_pos;
+        }
+        let word = words
+            .get(&word_for_tick(tick, 1, lte))
+            .copied()
+            .unwrap_or_default();
+        next_initialized_tick_within_one_word(word, tick, 1, lte)
+    }
+
+    #[test]
+    fn searching_right() {
+        assert_eq!(next(&TICKS, 78, false), (84, true));
+        assert_eq!(next(&TICKS, -55, false), (-4, true));
+        assert_eq!(next(&TICKS, 77, false), (78, true));
+        assert_eq!(next(&TICKS, -56, false), (-55, true));
+        // Word boundaries
+        assert_eq!(next(&TICKS, 255, false), (511, false));
+        assert_eq!(next(&TICKS, -257, false), (-200, true));
+        assert_eq!(next(&TICKS, 340, false), (511, false));
+        assert_eq!(next(&TICKS, 508, false), (511, false));
+        assert_eq!(next(&TICKS, 383, false), (511, false));
+    }
+
+    #[test]
+    fn searching_left() {
+        assert_eq!(next(&TICKS, 78, true), (78, true));
+        assert_eq!(next(&TICKS, 79, true), (78, true));
+        assert_eq!(next(&TICKS, 72, true), (70, true));
+        // Word boundaries
+        assert_eq!(next(&TICKS, 258, true), (256, false));
+        assert_eq!(next(&TICKS, 256, true), (256, false));
+        assert_eq!(next(&TICKS, -257, true), (-512, false));
+        assert_eq!(next(&TICKS, 1023, true), (768, false));
+        assert_eq!(next(&TICKS, 900, true), (768, false));
+
+        let mut ticks = TICKS.to_vec();
+        ticks.push(329);
+        assert_eq!(next(&ticks, 456, true), (329, true));
+    }
+
+    #[test]
+    fn compresses_towards_negative_infinity() {
+        assert_eq!(compress(-1, 10), -1);
+        assert_eq!(compress(-10, 10), -1);
+        assert_eq!(compress(-11, 10), -2);
+        assert_eq!(compress(19, 10), 1);
+        assert_eq!(position(-1), (-1, 255));
+        assert_eq!(position(256), (1, 0));
+    }
+
+    #[test]
+    fn lists_initialized_ticks_of_a_word() {
+        let word = (U256::one() << 3) | (U256::one() << 255);
+        assert_eq!(initialized_ticks(-1, word, 10), vec![-2530, -10]);
+    }
+}
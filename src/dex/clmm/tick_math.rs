@@ -0,0 +1,157 @@
+/*
+ * Tick <-> sqrt price conversions (TickMath port)
+ */
+
+use crate::models::{ArgusError, Result};
+use ethers::types::U256;
+
+pub const MIN_TICK: i32 = -887_272;
+pub const MAX_TICK: i32 = 887_272;
+
+/// `getSqrtPriceAtTick(MIN_TICK)`
+pub const MIN_SQRT_PRICE: U256 = U256([4_295_128_739, 0, 0, 0]);
+
+/// `getSqrtPriceAtTick(MAX_TICK)`
+pub const MAX_SQRT_PRICE: U256 = U256([
+    0x5d95_1d52_6398_8d26,
+    0xefd1_fc6a_5064_8849,
+    0x0000_0000_fffd_8963,
+    0,
+]);
+
+// Multipliers for each set bit of |tick|, as Q128.128 values of 1 / sqrt(1.0001)^(2^i)
+const TICK_RATIOS: [(u32, u128); 19] = [
+    (0x2, 0xfff9_7272_373d_4132_59a4_6990_580e_213a),
+    (0x4, 0xfff2_e50f_5f65_6932_ef12_357c_f3c7_fdcc),
+    (0x8, 0xffe5_caca_7e10_e4e6_1c36_24ea_a094_1cd0),
+    (0x10, 0xffcb_9843_d60f_6159_c9db_5883_5c92_6644),
+    (0x20, 0xff97_3b41_fa98_c081_472e_6896_dfb2_54c0),
+    (0x40, 0xff2e_a164_66c9_6a38_43ec_78b3_26b5_2861),
+    (0x80, 0xfe5d_ee04_6a99_a2a8_11c4_61f1_969c_3053),
+    (0x100, 0xfcbe_86c7_900a_88ae_dcff_c83b_479a_a3a4),
+    (0x200, 0xf987_a725_3ac4_1317_6f2b_074c_f781_5e54),
+    (0x400, 0xf339_2b08_22b7_0005_940c_7a39_8e4b_70f3),
+    (0x800, 0xe715_9475_a2c2_9b74_43b2_9c7f_a6e8_89d9),
+    (0x1000, 0xd097_f3bd_fd20_22b8_845a_d8f7_92aa_5825),
+    (0x2000, 0xa9f7_4646_2d87_0fdf_8a65_dc1f_90e0_61e5),
+    (0x4000, 0x70d8_69a1_56d2_a1b8_90bb_3df6_2baf_32f7),
+    (0x8000, 0x31be_135f_97d0_8fd9_8123_1505_542f_cfa6),
+    (0x1_0000, 0x9aa_508b_5b7a_84e1_c677_de54_f3e9_9bc9),
+    (0x2_0000, 0x5d_6af8_dedb_8119_6699_c329_225e_e604),
+    (0x4_0000, 0x2216_e584_f5fa_1ea9_2604_1bed_fe98),
+    (0x8_0000, 0x48a_1703_91f7_dc42_444e_8fa2),
+];
+
+/// Returns `sqrt(1.0001^tick) * 2^96`, bit-for-bit identical to `TickMath.getSqrtPriceAtTick`.
+pub fn get_sqrt_price_at_tick(tick: i32) -> Result<U256> {
+    if !(MIN_TICK..=MAX_TICK).contains(&tick) {
+        return Err(ArgusError::CalculationError(format!(
+            "Tick {tick} out of range"
+        )));
+    }
+
+    let abs_tick = tick.unsigned_abs();
+
+    let mut ratio = if abs_tick & 0x1 != 0 {
+        U256::from(0xfffc_b933_bd6f_ad37_aa2d_162d_1a59_4001_u128)
+    } else {
+        U256::one() << 128
+    };
+
+    for (bit, multiplier) in TICK_RATIOS {
+        if abs_tick & bit != 0 {
+            ratio = (ratio * U256::from(multiplier)) >> 128;
+        }
+    }
+
+    if tick > 0 {
+        ratio = U256::MAX / ratio;
+    }
+
+    let remainder = ratio & U256::from(u32::MAX);
+    Ok((ratio >> 32) + u8::from(!remainder.is_zero()))
+}
+
+/// Returns the greatest tick whose sqrt price is less than or equal to `sqrt_price_x96`,
+/// matching `TickMath.getTickAtSqrtPrice`.
+pub fn get_tick_at_sqrt_price(sqrt_price_x96: U256) -> Result<i32> {
+    if sqrt_price_x96 < MIN_SQRT_PRICE || sqrt_price_x96 >= MAX_SQRT_PRICE {
+        return Err(ArgusError::CalculationError(format!(
+            "Sqrt price {sqrt_price_x96} out of range"
+        )));
+    }
+
+    // getSqrtPriceAtTick is strictly monotonic, so a binary search yields the same
+    // tick as the on-chain log2 approximation
+    let mut low = MIN_TICK;
+    let mut high = MAX_TICK;
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        if get_sqrt_price_at_tick(mid)? <= sqrt_price_x96 {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    Ok(low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u256(value: &str) -> U256 {
+        U256::from_dec_str(value).unwrap()
+    }
+
+    #[test]
+    fn sqrt_price_at_tick_matches_tick_math() {
+        assert_eq!(get_sqrt_price_at_tick(MIN_TICK).unwrap(), MIN_SQRT_PRICE);
+        assert_eq!(get_sqrt_price_at_tick(MAX_TICK).unwrap(), MAX_SQRT_PRICE);
+        assert_eq!(
+            MAX_SQRT_PRICE,
+            u256("1461446703485210103287273052203988822378723970342")
+        );
+        assert_eq!(get_sqrt_price_at_tick(0).unwrap(), U256::one() << 96);
+        assert_eq!(
+            get_sqrt_price_at_tick(MIN_TICK + 1).unwrap(),
+            u256("4295343490")
+        );
+        assert_eq!(
+            get_sqrt_price_at_tick(MAX_TICK - 1).unwrap(),
+            u256("1461373636630004318706518188784493106690254656249")
+        );
+        assert_eq!(
+            get_sqrt_price_at_tick(50).unwrap(),
+            u256("79426470787362580746886972461")
+        );
+    }
+
+    #[test]
+    fn sqrt_price_at_tick_rejects_out_of_range_ticks() {
+        assert!(get_sqrt_price_at_tick(MIN_TICK - 1).is_err());
+        assert!(get_sqrt_price_at_tick(MAX_TICK + 1).is_err());
+    }
+
+    #[test]
+    fn tick_at_sqrt_price_round_trips() {
+        for tick in [MIN_TICK, -200_000, -60, -1, 0, 1, 60, 200_000, MAX_TICK - 1] {
+            let sqrt_price = get_sqrt_price_at_tick(tick).unwrap();
+            assert_eq!(get_tick_at_sqrt_price(sqrt_price).unwrap(), tick);
+            if tick > MIN_TICK {
+                assert_eq!(get_tick_at_sqrt_price(sqrt_price - 1).unwrap(), tick - 1);
+            }
+        }
+        assert_eq!(
+            get_tick_at_sqrt_price(MAX_SQRT_PRICE - 1).unwrap(),
+            MAX_TICK - 1
+        );
+    }
+
+    #[test]
+    fn tick_at_sqrt_price_rejects_out_of_range_prices() {
+        assert!(get_tick_at_sqrt_price(MIN_SQRT_PRICE - 1).is_err());
+        assert!(get_tick_at_sqrt_price(MAX_SQRT_PRICE).is_err());
+    }
+}
@@ -3,31 +3,50 @@
  */
 
 pub mod aerodrome;
+pub mod clmm;
+pub mod pool_cache;
+pub mod state_override;
 pub mod uniswap_v4;
 
 use crate::models::Result;
+use crate::utils::raw_to_decimal;
 use async_trait::async_trait;
+use ethers::types::{spoof, Address, U256};
 use rust_decimal::Decimal;
 
+/// Pool reads are pinned to an explicit `block_number`, so quotes taken across several
+/// calls all see the same pool state.
 #[async_trait]
 pub trait DexClient: Send + Sync {
-    async fn get_pool_state(&self) -> Result<PoolState>;
+    async fn get_pool_state(&self, block_number: u64) -> Result<PoolState>;
     async fn calculate_swap_output(
         &self,
         amount_in: Decimal,
         zero_for_one: bool,
+        block_number: u64,
     ) -> Result<SwapQuote>;
     async fn estimate_gas(&self) -> Result<u64>;
+    async fn build_swap_calldata(&self, params: &SwapCalldataParams) -> Result<SwapCalldata>;
 }
 
 #[derive(Debug, Clone)]
 pub struct PoolState {
     pub sqrt_price_x96: u128,
     pub tick: i32,
+    /// Liquidity active at the current price; `sqrt(reserve0 * reserve1)` for reserve pools
     pub liquidity: u128,
     pub fee: u32,
 }
 
+impl PoolState {
+    /// Value of the active liquidity in token1, which has `decimals1` decimals: the token1
+    /// virtual reserve `L * sqrtP` plus the token0 virtual reserve, which is worth as much.
+    pub fn active_liquidity_in_token1(&self, decimals1: u32) -> Result<Decimal> {
+        let reserve1 = (U256::from(self.liquidity) * U256::from(self.sqrt_price_x96)) >> 96;
+        Ok(raw_to_decimal(reserve1, decimals1)? * Decimal::TWO)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SwapQuote {
     pub amount_out: Decimal,
@@ -35,3 +54,25 @@ pub struct SwapQuote {
     pub price_impact: Decimal,
     pub gas_estimate: u64,
 }
+
+/// Inputs for encoding an exact-input router swap, in human-readable token units.
+/// The swap is sent from `recipient`.
+#[derive(Debug, Clone)]
+pub struct SwapCalldataParams {
+    pub amount_in: Decimal,
+    pub min_amount_out: Decimal,
+    pub zero_for_one: bool,
+    pub recipient: Address,
+    pub deadline: u64,
+}
+
+/// A ready-to-send router transaction, plus the state overrides that fund `from` with the
+/// input tokens and approvals it needs to execute.
+#[derive(Debug, Clone)]
+pub struct SwapCalldata {
+    pub from: Address,
+    pub to: Address,
+    pub data: Vec<u8>,
+    pub value: U256,
+    pub state_overrides: spoof::State,
+}
@@ -0,0 +1,210 @@
+/*
+ * Pool state cache kept current by applying pool events to an eth_call snapshot
+ */
+
+use crate::models::Result;
+use crate::rpc::RpcClient;
+use async_trait::async_trait;
+use ethers::types::{Block, Filter, Log, H256};
+use futures::StreamExt;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast::error::TryRecvError;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+// Blocks after which the state is re-read from the chain, picking up changes such as fee
+// updates that emit no event the cache follows
+const RESYNC_INTERVAL_BLOCKS: u64 = 600;
+
+// Widest block range fetched with one eth_getLogs call; larger gaps re-read the state
+const MAX_LOG_RANGE_BLOCKS: u64 = 500;
+
+/// A pool whose state can be rebuilt from an `eth_call` snapshot plus the events it emits.
+#[async_trait]
+pub trait EventSourcedPool: Send + Sync + 'static {
+    type State: Clone + Send + Sync + 'static;
+
+    fn name(&self) -> &'static str;
+    fn rpc(&self) -> &Arc<RpcClient>;
+    fn cache(&self) -> &PoolStateCache<Self::State>;
+
+    /// Filter selecting every event that changes the cached state, without a block range.
+    fn event_filter(&self) -> Filter;
+
+    /// Reads the state from the chain at `block_number`.
+    async fn read_state(&self, block_number: u64) -> Result<Self::State>;
+
+    /// Applies one event matched by `event_filter` to `state`.
+    fn apply_event(&self, state: &mut Self::State, log: &Log) -> Result<()>;
+}
+
+#[derive(Debug, Clone)]
+struct CachedState<S> {
+    block_number: u64,
+    synced_at_block: u64,
+    state: S,
+}
+
+/// Latest known state of one pool and the block it is valid at.
+pub struct PoolStateCache<S> {
+    current: RwLock<Option<CachedState<S>>>,
+}
+
+impl<S: Clone> Default for PoolStateCache<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Clone> PoolStateCache<S> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            current: RwLock::new(None),
+        }
+    }
+
+    /// The cached state if it is valid at exactly `block_number`.
+    pub fn get(&self, block_number: u64) -> Option<S> {
+        self.current
+            .read()
+            .unwrap()
+            .as_ref()
+            .filter(|cached| cached.block_number == block_number)
+            .map(|cached| cached.state.clone())
+    }
+
+    pub fn clear(&self) {
+        *self.current.write().unwrap() = None;
+    }
+
+    fn snapshot(&self) -> Option<CachedState<S>> {
+        self.current.read().unwrap().clone()
+    }
+
+    fn store(&self, cached: CachedState<S>) {
+        *self.current.write().unwrap() = Some(cached);
+    }
+}
+
+/// State of `pool` at `block_number`: served from the cache when it is at that block,
+/// otherwise read from the chain.
+pub async fn state_at<P: EventSourcedPool>(pool: &P, block_number: u64) -> Result<P::State> {
+    match pool.cache().get(block_number) {
+        Some(state) => Ok(state),
+        None => pool.read_state(block_number).await,
+    }
+}
+
+/// Follows the chain's new blocks and advances `pool`'s cache with the events of each
+/// one, bootstrapping from an `eth_call` snapshot first. On any error, and after a reorg
+/// that may have orphaned the cached block, the cache is cleared, so reads fall back to
+/// the chain until the next block re-bootstraps it.
+pub fn spawn_pool_sync<P: EventSourcedPool>(
+    pool: Arc<P>,
+    poll_interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!("Starting {} pool state sync", pool.name());
+        let mut reorgs = pool.rpc().subscribe_reorgs();
+
+        loop {
+            let mut blocks = match pool.rpc().new_blocks(poll_interval).await {
+                Ok(blocks) => blocks,
+                Err(e) => {
+                    warn!(
+                        "{} pool sync failed to open block stream: {}",
+                        pool.name(),
+                        e
+                    );
+                    tokio::time::sleep(poll_interval).await;
+                    continue;
+                }
+            };
+
+            while let Some(block) = blocks.next().await {
+                // Reorgs are detected while a block is fetched, so they are queued by now
+                let mut reorged = false;
+                while let Err(TryRecvError::Lagged(_)) | Ok(_) = reorgs.try_recv() {
+                    reorged = true;
+                }
+                if reorged {
+                    info!("{} pool state dropped after a reorg", pool.name());
+                    pool.cache().clear();
+                }
+
+                let result = match block {
+                    Ok(block) => advance(pool.as_ref(), &block).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    warn!("{} pool state sync failed: {}", pool.name(), e);
+                    pool.cache().clear();
+                }
+            }
+
+            warn!(
+                "{} pool sync block stream ended, resubscribing",
+                pool.name()
+            );
+            tokio::time::sleep(poll_interval).await;
+        }
+    })
+}
+
+async fn advance<P: EventSourcedPool>(pool: &P, block: &Block<H256>) -> Result<()> {
+    let Some(head) = block.number.map(|number| number.as_u64()) else {
+        return Ok(());
+    };
+
+    let cached = match pool.cache().snapshot() {
+        Some(cached) if cached.block_number >= head => return Ok(()),
+        Some(cached)
+            if head - cached.block_number <= MAX_LOG_RANGE_BLOCKS
+                && head - cached.synced_at_block < RESYNC_INTERVAL_BLOCKS =>
+        {
+            cached
+        }
+        _ => {
+            let state = pool.read_state(head).await?;
+            pool.cache().store(CachedState {
+                block_number: head,
+                synced_at_block: head,
+                state,
+            });
+            debug!(
+                "{} pool state read from chain at block {}",
+                pool.name(),
+                head
+            );
+            return Ok(());
+        }
+    };
+
+    let filter = pool
+        .event_filter()
+        .from_block(cached.block_number + 1)
+        .to_block(head);
+    let mut logs = pool.rpc().get_logs(&filter).await?;
+    logs.retain(|log| log.removed != Some(true));
+    logs.sort_by_key(|log| (log.block_number, log.log_index));
+
+    let mut state = cached.state;
+    for log in &logs {
+        pool.apply_event(&mut state, log)?;
+    }
+
+    pool.cache().store(CachedState {
+        block_number: head,
+        synced_at_block: cached.synced_at_block,
+        state,
+    });
+    debug!(
+        "{} pool state advanced to block {} with {} events",
+        pool.name(),
+        head,
+        logs.len()
+    );
+    Ok(())
+}
@@ -0,0 +1,118 @@
+/*
+ * State overrides that fund a synthetic sender so router swaps can be gas-estimated
+ */
+
+use crate::models::{ArgusError, Result};
+use ethers::{
+    abi::{encode, Token},
+    types::{spoof, Address, H256, U256},
+    utils::keccak256,
+};
+use std::str::FromStr;
+
+pub const PERMIT2_ADDRESS: &str = "0x000000000022D473030F116dDEE9F6B43aC78BA3";
+
+// `allowance` follows SignatureTransfer's `nonceBitmap` in Permit2's storage
+const PERMIT2_ALLOWANCE_SLOT: u64 = 1;
+
+// Native balance on top of the swap value so the sender can always pay for gas
+const GAS_MONEY_WEI: u128 = 100_000_000_000_000_000_000;
+
+// Storage slots of the `balanceOf` and `allowance` mappings of supported tokens
+const ERC20_LAYOUTS: &[(&str, u64, u64)] = &[
+    // USDC (FiatTokenV2_2) on Ethereum
+    ("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48", 9, 10),
+    // USDC (FiatTokenV2_2) on Base
+    ("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913", 9, 10),
+    // WETH9 on Ethereum
+    ("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2", 3, 4),
+    // WETH9 predeploy on Base
+    ("0x4200000000000000000000000000000000000006", 3, 4),
+];
+
+/// Gives `owner` enough native balance to send `value` and pay for gas.
+pub fn fund_native(state: &mut spoof::State, owner: Address, value: U256) {
+    state
+        .account(owner)
+        .balance(value + U256::from(GAS_MONEY_WEI));
+}
+
+/// Sets `owner`'s `token` balance to `amount` and approves `spender` for the same amount.
+pub fn fund_erc20(
+    state: &mut spoof::State,
+    token: Address,
+    owner: Address,
+    spender: Address,
+    amount: U256,
+) -> Result<()> {
+    let (balance_slot, allowance_slot) = erc20_layout(token)?;
+
+    let balance_key = mapping_slot(owner, H256::from_low_u64_be(balance_slot));
+    let allowance_key = mapping_slot(
+        spender,
+        mapping_slot(owner, H256::from_low_u64_be(allowance_slot)),
+    );
+
+    state
+        .account(token)
+        .store(balance_key, u256_to_h256(amount))
+        .store(allowance_key, u256_to_h256(amount));
+    Ok(())
+}
+
+/// Grants `spender` a non-expiring Permit2 allowance over `owner`'s `token`.
+pub fn approve_permit2(
+    state: &mut spoof::State,
+    owner: Address,
+    token: Address,
+    spender: Address,
+    amount: U256,
+) -> Result<()> {
+    let permit2 = Address::from_str(PERMIT2_ADDRESS)
+        .map_err(|e| ArgusError::ContractError(format!("Invalid Permit2 address: {e}")))?;
+
+    if amount.bits() > 160 {
+        return Err(ArgusError::CalculationError(format!(
+            "Permit2 allowance does not fit in uint160: {amount}"
+        )));
+    }
+
+    // PackedAllowance { uint160 amount; uint48 expiration; uint48 nonce }
+    let expiration = U256::from(0xffff_ffff_ffff_u64);
+    let packed = amount | (expiration << 160);
+
+    let key = mapping_slot(
+        spender,
+        mapping_slot(
+            token,
+            mapping_slot(owner, H256::from_low_u64_be(PERMIT2_ALLOWANCE_SLOT)),
+        ),
+    );
+
+    state.account(permit2).store(key, u256_to_h256(packed));
+    Ok(())
+}
+
+fn erc20_layout(token: Address) -> Result<(u64, u64)> {
+    ERC20_LAYOUTS
+        .iter()
+        .find(|(address, _, _)| Address::from_str(address).is_ok_and(|address| address == token))
+        .map(|(_, balance_slot, allowance_slot)| (*balance_slot, *allowance_slot))
+        .ok_or_else(|| {
+            ArgusError::ContractError(format!("No known storage layout for token {token:?}"))
+        })
+}
+
+/// Storage slot of `mapping[key]` for a mapping rooted at `slot`.
+fn mapping_slot(key: Address, slot: H256) -> H256 {
+    H256::from(keccak256(encode(&[
+        Token::Address(key),
+        Token::FixedBytes(slot.as_bytes().to_vec()),
+    ])))
+}
+
+fn u256_to_h256(value: U256) -> H256 {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    H256::from(bytes)
+}
@@ -3,84 +3,132 @@
  */
 
 use super::types::PoolKey;
-use crate::dex::{DexClient, PoolState, SwapQuote};
+use crate::dex::clmm::sqrt_price_math::sqrt_price_to_u128;
+use crate::dex::clmm::{self, SwapStartState, TickDataProvider};
+use crate::dex::pool_cache::{self, EventSourcedPool, PoolStateCache};
+use crate::dex::{
+    state_override, DexClient, PoolState, SwapCalldata, SwapCalldataParams, SwapQuote,
+};
 use crate::models::{ArgusError, Result};
-use crate::rpc::RpcClient;
+use crate::rpc::{Call3, RpcClient};
 use async_trait::async_trait;
 use ethers::{
     abi::{encode, Token},
-    providers::Middleware,
-    types::{Address, U256},
+    types::{spoof, Address, Filter, Log, H256, I256, U256},
     utils::keccak256,
 };
 use rust_decimal::Decimal;
 use std::str::FromStr;
 use std::sync::Arc;
 
+const ETH_DECIMALS: u32 = 18;
+const USDC_DECIMALS: u32 = 6;
+
+// PoolManager events that change the cached slot0 and active liquidity
+const SWAP_EVENT: &str = "Swap(bytes32,address,int128,int128,uint160,uint128,int24,uint24)";
+const MODIFY_LIQUIDITY_EVENT: &str = "ModifyLiquidity(bytes32,address,int24,int24,int256,bytes32)";
+const PROTOCOL_FEE_UPDATED_EVENT: &str = "ProtocolFeeUpdated(bytes32,uint24)";
+
 pub struct UniswapV4Client {
     rpc: Arc<RpcClient>,
     state_view: Address,
+    pool_manager: Address,
     pool_key: PoolKey,
+    cache: PoolStateCache<PoolSnapshot>,
+}
+
+/// Slot0 and active liquidity of the pool at one block.
+#[derive(Debug, Clone, Copy)]
+pub struct PoolSnapshot {
+    sqrt_price_x96: u128,
+    tick: i32,
+    protocol_fee: u32,
+    lp_fee: u32,
+    liquidity: u128,
 }
 
 impl UniswapV4Client {
     pub fn new(rpc: Arc<RpcClient>) -> Result<Self> {
+        Self::with_pool_key(rpc, PoolKey::new_weth_usdc())
+    }
+
+    /// Client for another ETH/USDC pool, such as one with a different fee tier or hooks.
+    pub fn with_pool_key(rpc: Arc<RpcClient>, pool_key: PoolKey) -> Result<Self> {
         let state_view = Address::from_str(super::STATE_VIEW_ADDRESS)
             .map_err(|e| ArgusError::ContractError(format!("Invalid StateView address: {e}")))?;
 
-        let pool_key = PoolKey::new_weth_usdc();
+        let pool_manager = Address::from_str(super::POOL_MANAGER_ADDRESS)
+            .map_err(|e| ArgusError::ContractError(format!("Invalid PoolManager address: {e}")))?;
 
         Ok(Self {
             rpc,
             state_view,
+            pool_manager,
             pool_key,
+            cache: PoolStateCache::new(),
         })
     }
 
-    async fn get_pool_state_internal(&self) -> Result<PoolState> {
-        let slot0_data = self.read_slot0().await?;
-        let liquidity = self.read_liquidity().await?;
+    async fn get_pool_state_internal(&self, block_number: u64) -> Result<PoolState> {
+        let snapshot = pool_cache::state_at(self, block_number).await?;
 
         Ok(PoolState {
-            sqrt_price_x96: slot0_data.0,
-            tick: slot0_data.1,
-            liquidity,
-            fee: slot0_data.3,
+            sqrt_price_x96: snapshot.sqrt_price_x96,
+            tick: snapshot.tick,
+            liquidity: snapshot.liquidity,
+            fee: snapshot.lp_fee,
         })
     }
 
-    async fn read_slot0(&self) -> Result<(u128, i32, u32, u32)> {
-        let provider = self.rpc.provider();
+    fn state_view_calldata(&self, signature: &str, args: &[Token]) -> Vec<u8> {
+        let function_selector = &keccak256(signature.as_bytes())[0..4];
 
-        let pool_id = self.pool_key.to_id();
-        let function_selector = &keccak256(b"getSlot0(bytes32)")[0..4];
+        let mut params = vec![Token::FixedBytes(self.pool_key.to_id().to_vec())];
+        params.extend_from_slice(args);
 
-        let encoded_params = encode(&[Token::FixedBytes(pool_id.to_vec())]);
         let mut call_data = Vec::from(function_selector);
-        call_data.extend_from_slice(&encoded_params);
-
-        let tx = ethers::types::TransactionRequest::new()
-            .to(self.state_view)
-            .data(ethers::types::Bytes::from(call_data));
+        call_data.extend_from_slice(&encode(&params));
+        call_data
+    }
 
-        let result = provider
-            .call(&tx.into(), None)
-            .await
-            .map_err(|e| ArgusError::ContractError(format!("Failed to call getSlot0: {e}")))?;
+    fn state_view_call(&self, signature: &str, args: &[Token]) -> Call3 {
+        Call3::new(self.state_view, self.state_view_calldata(signature, args))
+    }
 
-        if result.len() < 128 {
-            return Err(ArgusError::ContractError(
-                "Invalid slot0 response".to_string(),
-            ));
-        }
+    /// Reads slot0 and liquidity in one batch at `block_number`.
+    async fn read_snapshot(&self, block_number: u64) -> Result<PoolSnapshot> {
+        let result = self
+            .rpc
+            .multicall(
+                &[
+                    self.state_view_call("getSlot0(bytes32)", &[]),
+                    self.state_view_call("getLiquidity(bytes32)", &[]),
+                ],
+                Some(block_number),
+                "getSlot0+getLiquidity",
+            )
+            .await?;
+
+        let (sqrt_price_x96, tick, protocol_fee, lp_fee) =
+            Self::decode_slot0(result.output(0, "getSlot0", 128)?)?;
+        let liquidity = U256::from_big_endian(&result.output(1, "getLiquidity", 32)?[0..32]);
+
+        Ok(PoolSnapshot {
+            sqrt_price_x96,
+            tick,
+            protocol_fee,
+            lp_fee,
+            liquidity: liquidity.as_u128(),
+        })
+    }
 
+    fn decode_slot0(result: &[u8]) -> Result<(u128, i32, u32, u32)> {
         let sqrt_price_bytes = &result[0..32];
         let tick_bytes = &result[32..64];
         let protocol_fee_bytes = &result[64..96];
         let lp_fee_bytes = &result[96..128];
 
-        let sqrt_price = U256::from_big_endian(sqrt_price_bytes);
-        let sqrt_price_u128 = sqrt_price.as_u128();
+        let sqrt_price_u128 = sqrt_price_to_u128(U256::from_big_endian(sqrt_price_bytes))?;
 
         let tick_i32 = if tick_bytes[29] >= 0x80 {
             let val = (i32::from(tick_bytes[29]) << 16)
@@ -105,52 +153,223 @@ impl UniswapV4Client {
         Ok((sqrt_price_u128, tick_i32, protocol_fee_u32, lp_fee_u32))
     }
 
-    async fn read_liquidity(&self) -> Result<u128> {
-        let provider = self.rpc.provider();
+    /// Effective swap fee in pips, combining the directional protocol fee with the LP fee
+    /// the same way `ProtocolFeeLibrary.calculateSwapFee` does.
+    fn swap_fee_pips(protocol_fee: u32, lp_fee: u32, zero_for_one: bool) -> u32 {
+        let protocol_fee = if zero_for_one {
+            protocol_fee & 0xfff
+        } else {
+            protocol_fee >> 12
+        };
 
-        let pool_id = self.pool_key.to_id();
-        let function_selector = &keccak256(b"getLiquidity(bytes32)")[0..4];
+        if protocol_fee == 0 {
+            return lp_fee;
+        }
 
-        let encoded_params = encode(&[Token::FixedBytes(pool_id.to_vec())]);
-        let mut call_data = Vec::from(function_selector);
-        call_data.extend_from_slice(&encoded_params);
+        let lp_fee = lp_fee & 0x00ff_ffff;
+        protocol_fee + lp_fee - protocol_fee * lp_fee / clmm::swap_math::MAX_SWAP_FEE
+    }
+}
+
+#[async_trait]
+impl TickDataProvider for UniswapV4Client {
+    async fn get_tick_bitmap_words(
+        &self,
+        word_positions: &[i16],
+        block_number: u64,
+    ) -> Result<Vec<U256>> {
+        let calls: Vec<Call3> = word_positions
+            .iter()
+            .map(|word_pos| {
+                self.state_view_call(
+                    "getTickBitmap(bytes32,int16)",
+                    &[Token::Int(I256::from(*word_pos).into_raw())],
+                )
+            })
+            .collect();
+
+        let result = self
+            .rpc
+            .multicall(&calls, Some(block_number), "getTickBitmap")
+            .await?;
+
+        (0..calls.len())
+            .map(|index| {
+                let word = result.output(index, "getTickBitmap", 32)?;
+                Ok(U256::from_big_endian(&word[0..32]))
+            })
+            .collect()
+    }
 
-        let tx = ethers::types::TransactionRequest::new()
-            .to(self.state_view)
-            .data(ethers::types::Bytes::from(call_data));
+    async fn get_liquidity_nets(&self, ticks: &[i32], block_number: u64) -> Result<Vec<i128>> {
+        let calls: Vec<Call3> = ticks
+            .iter()
+            .map(|tick| {
+                self.state_view_call(
+                    "getTickLiquidity(bytes32,int24)",
+                    &[Token::Int(I256::from(*tick).into_raw())],
+                )
+            })
+            .collect();
+
+        let result = self
+            .rpc
+            .multicall(&calls, Some(block_number), "getTickLiquidity")
+            .await?;
+
+        (0..calls.len())
+            .map(|index| {
+                let liquidity = result.output(index, "getTickLiquidity", 64)?;
+                Ok(I256::from_raw(U256::from_big_endian(&liquidity[32..64])).as_i128())
+            })
+            .collect()
+    }
+}
 
-        let result = provider
-            .call(&tx.into(), None)
-            .await
-            .map_err(|e| ArgusError::ContractError(format!("Failed to call getLiquidity: {e}")))?;
+#[async_trait]
+impl EventSourcedPool for UniswapV4Client {
+    type State = PoolSnapshot;
 
-        if result.len() < 32 {
-            return Err(ArgusError::ContractError(
-                "Invalid liquidity response".to_string(),
-            ));
+    fn name(&self) -> &'static str {
+        "Uniswap V4"
+    }
+
+    fn rpc(&self) -> &Arc<RpcClient> {
+        &self.rpc
+    }
+
+    fn cache(&self) -> &PoolStateCache<PoolSnapshot> {
+        &self.cache
+    }
+
+    fn event_filter(&self) -> Filter {
+        Filter::new()
+            .address(self.pool_manager)
+            .topic0(
+                [
+                    SWAP_EVENT,
+                    MODIFY_LIQUIDITY_EVENT,
+                    PROTOCOL_FEE_UPDATED_EVENT,
+                ]
+                .map(|event| H256::from(keccak256(event)))
+                .to_vec(),
+            )
+            .topic1(H256::from(self.pool_key.to_id()))
+    }
+
+    async fn read_state(&self, block_number: u64) -> Result<PoolSnapshot> {
+        self.read_snapshot(block_number).await
+    }
+
+    fn apply_event(&self, state: &mut PoolSnapshot, log: &Log) -> Result<()> {
+        let topic = log.topics.first().copied().unwrap_or_default();
+        let word = |index: usize| {
+            log.data
+                .get(index * 32..(index + 1) * 32)
+                .map(U256::from_big_endian)
+                .ok_or_else(|| {
+                    ArgusError::ContractError(format!(
+                        "Truncated PoolManager event in tx {:?}",
+                        log.transaction_hash
+                    ))
+                })
+        };
+
+        if topic == H256::from(keccak256(SWAP_EVENT)) {
+            state.sqrt_price_x96 = sqrt_price_to_u128(word(2)?)?;
+            state.liquidity = word(3)?.as_u128();
+            state.tick = I256::from_raw(word(4)?).as_i32();
+            // Dynamic-fee pools change the fee without a dedicated event
+            state.lp_fee = word(5)?.low_u32() & 0x00ff_ffff;
+        } else if topic == H256::from(keccak256(MODIFY_LIQUIDITY_EVENT)) {
+            let tick_lower = I256::from_raw(word(0)?).as_i32();
+            let tick_upper = I256::from_raw(word(1)?).as_i32();
+            // Only positions spanning the current tick change the active liquidity
+            if tick_lower <= state.tick && state.tick < tick_upper {
+                let delta = i128::try_from(I256::from_raw(word(2)?)).map_err(|e| {
+                    ArgusError::ContractError(format!("Invalid liquidity delta: {e}"))
+                })?;
+                state.liquidity = clmm::add_delta(state.liquidity, delta)?;
+            }
+        } else if topic == H256::from(keccak256(PROTOCOL_FEE_UPDATED_EVENT)) {
+            state.protocol_fee = word(0)?.as_u32();
         }
 
-        let liquidity = U256::from_big_endian(&result[0..32]);
-        Ok(liquidity.as_u128())
+        Ok(())
     }
 }
 
 #[async_trait]
 impl DexClient for UniswapV4Client {
-    async fn get_pool_state(&self) -> Result<PoolState> {
-        self.get_pool_state_internal().await
+    async fn get_pool_state(&self, block_number: u64) -> Result<PoolState> {
+        self.get_pool_state_internal(block_number).await
     }
 
     async fn calculate_swap_output(
         &self,
         amount_in: Decimal,
         zero_for_one: bool,
+        block_number: u64,
     ) -> Result<SwapQuote> {
-        let pool_state = self.get_pool_state().await?;
+        if amount_in <= Decimal::ZERO {
+            tracing::error!("Attempted to calculate Uniswap V4 swap with non-positive input");
+            return Err(ArgusError::CalculationError(
+                "Cannot simulate swap with non-positive input amount".to_string(),
+            ));
+        }
+
+        let PoolSnapshot {
+            sqrt_price_x96,
+            tick,
+            protocol_fee,
+            lp_fee,
+            liquidity,
+        } = pool_cache::state_at(self, block_number).await?;
+
+        let (decimals_in, decimals_out) = if zero_for_one {
+            (ETH_DECIMALS, USDC_DECIMALS)
+        } else {
+            (USDC_DECIMALS, ETH_DECIMALS)
+        };
+
+        let start = SwapStartState {
+            block_number,
+            sqrt_price_x96: U256::from(sqrt_price_x96),
+            tick,
+            liquidity,
+            tick_spacing: self.pool_key.tick_spacing,
+            fee_pips: Self::swap_fee_pips(protocol_fee, lp_fee, zero_for_one),
+        };
+
+        let simulation = clmm::simulate_exact_input(
+            self,
+            start,
+            crate::utils::decimal_to_raw(amount_in, decimals_in)?,
+            zero_for_one,
+        )
+        .await?;
+
+        if simulation.amount_out.is_zero() {
+            return Err(ArgusError::CalculationError(
+                "Uniswap V4 swap simulation returned zero output".to_string(),
+            ));
+        }
+
+        let amount_in_used = crate::utils::raw_to_decimal(simulation.amount_in, decimals_in)?;
+        let amount_out = crate::utils::raw_to_decimal(simulation.amount_out, decimals_out)?;
+
+        tracing::debug!(
+            "Uniswap V4 swap simulation: ticks_crossed={}, tick {} -> {}, liquidity {} -> {}",
+            simulation.ticks_crossed,
+            tick,
+            simulation.tick_after,
+            liquidity,
+            simulation.liquidity_after
+        );
 
         #[allow(clippy::cast_precision_loss)]
-        let sqrt_price_f64 = pool_state.sqrt_price_x96 as f64 / (1u128 << 96) as f64;
-        let spot_price_raw = sqrt_price_f64 * sqrt_price_f64;
+        let sqrt_price = sqrt_price_x96 as f64 / (1u128 << 96) as f64;
+        let spot_price_raw = sqrt_price * sqrt_price;
 
         let spot_price = Decimal::try_from(spot_price_raw * 1e12).map_err(|e| {
             tracing::error!(
@@ -163,32 +382,26 @@ impl DexClient for UniswapV4Client {
             ))
         })?;
 
-        let price_impact_percent = if amount_in > Decimal::ZERO {
-            -((amount_in / Decimal::from(10)) * Decimal::from_str("0.001").unwrap())
-        } else {
-            Decimal::ZERO
-        };
-
-        let effective_price = if zero_for_one {
-            spot_price * (Decimal::ONE + price_impact_percent)
+        let (effective_price, price_impact) = if zero_for_one {
+            (
+                amount_out / amount_in_used,
+                crate::utils::calculate_price_impact(amount_in_used, amount_out, spot_price),
+            )
         } else {
-            spot_price * (Decimal::ONE - price_impact_percent)
+            (
+                amount_in_used / amount_out,
+                crate::utils::calculate_price_impact(
+                    amount_in_used,
+                    amount_out,
+                    Decimal::ONE / spot_price,
+                ),
+            )
         };
 
-        let amount_out = if zero_for_one {
-            amount_in * effective_price
-        } else {
-            amount_in / effective_price
-        };
-
-        let fee_multiplier =
-            Decimal::ONE - (Decimal::from(pool_state.fee) / Decimal::from(1_000_000));
-        let amount_out_after_fee = amount_out * fee_multiplier;
-
         Ok(SwapQuote {
-            amount_out: amount_out_after_fee,
+            amount_out,
             effective_price,
-            price_impact: price_impact_percent,
+            price_impact,
             gas_estimate: 150_000,
         })
     }
@@ -196,4 +409,68 @@ impl DexClient for UniswapV4Client {
     async fn estimate_gas(&self) -> Result<u64> {
         Ok(150_000)
     }
+
+    async fn build_swap_calldata(&self, params: &SwapCalldataParams) -> Result<SwapCalldata> {
+        let (decimals_in, decimals_out) = if params.zero_for_one {
+            (ETH_DECIMALS, USDC_DECIMALS)
+        } else {
+            (USDC_DECIMALS, ETH_DECIMALS)
+        };
+
+        let amount_in = crate::utils::decimal_to_raw(params.amount_in, decimals_in)?;
+        let min_amount_out = crate::utils::decimal_to_raw(params.min_amount_out, decimals_out)?;
+
+        let to = Address::from_str(super::UNIVERSAL_ROUTER_ADDRESS).map_err(|e| {
+            ArgusError::ContractError(format!("Invalid Universal Router address: {e}"))
+        })?;
+
+        // Native ETH input is paid with msg.value
+        let input_currency = if params.zero_for_one {
+            self.pool_key.currency0
+        } else {
+            self.pool_key.currency1
+        };
+        let value = if input_currency.is_zero() {
+            amount_in
+        } else {
+            U256::zero()
+        };
+
+        // ERC20 input is pulled by the router through Permit2
+        let mut state_overrides = spoof::state();
+        state_override::fund_native(&mut state_overrides, params.recipient, value);
+        if !input_currency.is_zero() {
+            let permit2 = Address::from_str(state_override::PERMIT2_ADDRESS)
+                .map_err(|e| ArgusError::ContractError(format!("Invalid Permit2 address: {e}")))?;
+            state_override::fund_erc20(
+                &mut state_overrides,
+                input_currency,
+                params.recipient,
+                permit2,
+                amount_in,
+            )?;
+            state_override::approve_permit2(
+                &mut state_overrides,
+                params.recipient,
+                input_currency,
+                to,
+                amount_in,
+            )?;
+        }
+
+        Ok(SwapCalldata {
+            from: params.recipient,
+            to,
+            data: super::router::encode_exact_input_single(
+                &self.pool_key,
+                params.zero_for_one,
+                amount_in,
+                min_amount_out,
+                params.recipient,
+                params.deadline,
+            ),
+            value,
+            state_overrides,
+        })
+    }
 }
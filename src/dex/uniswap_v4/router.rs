@@ -0,0 +1,142 @@
+/*
+ * Universal Router calldata encoding for Uniswap V4 swaps
+ */
+
+use super::types::PoolKey;
+use ethers::{
+    abi::{encode, Token},
+    types::{Address, I256, U256},
+    utils::keccak256,
+};
+
+/// Universal Router command that hands a `V4Router` action plan to the `PoolManager`.
+const V4_SWAP: u8 = 0x10;
+
+// V4Router actions (v4-periphery `Actions` library)
+const SWAP_EXACT_IN_SINGLE: u8 = 0x06;
+const SETTLE_ALL: u8 = 0x0c;
+const TAKE: u8 = 0x0e;
+
+/// Amount sentinel telling TAKE to withdraw the whole open delta.
+const OPEN_DELTA: u64 = 0;
+
+/// Encodes `UniversalRouter.execute(commands, inputs, deadline)` for a single-pool exact-input
+/// swap: swap, settle the input currency, and send the whole output to `recipient`.
+#[must_use]
+pub fn encode_exact_input_single(
+    pool_key: &PoolKey,
+    zero_for_one: bool,
+    amount_in: U256,
+    min_amount_out: U256,
+    recipient: Address,
+    deadline: u64,
+) -> Vec<u8> {
+    let (currency_in, currency_out) = if zero_for_one {
+        (pool_key.currency0, pool_key.currency1)
+    } else {
+        (pool_key.currency1, pool_key.currency0)
+    };
+
+    let swap_params = encode(&[Token::Tuple(vec![
+        Token::Tuple(vec![
+            Token::Address(pool_key.currency0),
+            Token::Address(pool_key.currency1),
+            Token::Uint(pool_key.fee.into()),
+            Token::Int(I256::from(pool_key.tick_spacing).into_raw()),
+            Token::Address(pool_key.hooks),
+        ]),
+        Token::Bool(zero_for_one),
+        Token::Uint(amount_in),
+        Token::Uint(min_amount_out),
+        Token::Bytes(Vec::new()),
+    ])]);
+    let settle_params = encode(&[Token::Address(currency_in), Token::Uint(amount_in)]);
+    let take_params = encode(&[
+        Token::Address(currency_out),
+        Token::Address(recipient),
+        Token::Uint(OPEN_DELTA.into()),
+    ]);
+
+    let v4_swap_input = encode(&[
+        Token::Bytes(vec![SWAP_EXACT_IN_SINGLE, SETTLE_ALL, TAKE]),
+        Token::Array(vec![
+            Token::Bytes(swap_params),
+            Token::Bytes(settle_params),
+            Token::Bytes(take_params),
+        ]),
+    ]);
+
+    let mut calldata = keccak256(b"execute(bytes,bytes[],uint256)")[0..4].to_vec();
+    calldata.extend_from_slice(&encode(&[
+        Token::Bytes(vec![V4_SWAP]),
+        Token::Array(vec![Token::Bytes(v4_swap_input)]),
+        Token::Uint(deadline.into()),
+    ]));
+    calldata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::utils::hex;
+
+    #[test]
+    fn encodes_execute_v4_swap_calldata() {
+        let recipient: Address = "0x000000000000000000000000000000000000dEaD"
+            .parse()
+            .unwrap();
+        let calldata = encode_exact_input_single(
+            &PoolKey::new_weth_usdc(),
+            true,
+            U256::exp10(18),
+            U256::from(3_000_000_000_u64),
+            recipient,
+            1_700_000_000,
+        );
+
+        // execute(bytes,bytes[],uint256), laid out word by word per the ABI spec
+        let words = [
+            "0000000000000000000000000000000000000000000000000000000000000060", // execute: offset of commands
+            "00000000000000000000000000000000000000000000000000000000000000a0", // offset of inputs
+            "000000000000000000000000000000000000000000000000000000006553f100", // deadline
+            "0000000000000000000000000000000000000000000000000000000000000001", // commands: length
+            "1000000000000000000000000000000000000000000000000000000000000000", // V4_SWAP
+            "0000000000000000000000000000000000000000000000000000000000000001", // inputs: length
+            "0000000000000000000000000000000000000000000000000000000000000020", // offset of inputs[0]
+            "0000000000000000000000000000000000000000000000000000000000000360", // inputs[0]: length
+            "0000000000000000000000000000000000000000000000000000000000000040", // V4_SWAP input: offset of actions
+            "0000000000000000000000000000000000000000000000000000000000000080", // offset of params
+            "0000000000000000000000000000000000000000000000000000000000000003", // actions: length
+            "060c0e0000000000000000000000000000000000000000000000000000000000", // SWAP_EXACT_IN_SINGLE, SETTLE_ALL, TAKE
+            "0000000000000000000000000000000000000000000000000000000000000003", // params: length
+            "0000000000000000000000000000000000000000000000000000000000000060", // offset of params[0]
+            "00000000000000000000000000000000000000000000000000000000000001e0", // offset of params[1]
+            "0000000000000000000000000000000000000000000000000000000000000240", // offset of params[2]
+            "0000000000000000000000000000000000000000000000000000000000000160", // params[0]: length
+            "0000000000000000000000000000000000000000000000000000000000000020", // offset of ExactInputSingleParams
+            "0000000000000000000000000000000000000000000000000000000000000000", // currency0: native ETH
+            "000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", // currency1: USDC
+            "00000000000000000000000000000000000000000000000000000000000001f4", // fee
+            "000000000000000000000000000000000000000000000000000000000000000a", // tickSpacing
+            "0000000000000000000000000000000000000000000000000000000000000000", // hooks
+            "0000000000000000000000000000000000000000000000000000000000000001", // zeroForOne
+            "0000000000000000000000000000000000000000000000000de0b6b3a7640000", // amountIn
+            "00000000000000000000000000000000000000000000000000000000b2d05e00", // amountOutMinimum
+            "0000000000000000000000000000000000000000000000000000000000000120", // offset of hookData
+            "0000000000000000000000000000000000000000000000000000000000000000", // hookData: length
+            "0000000000000000000000000000000000000000000000000000000000000040", // params[1]: length
+            "0000000000000000000000000000000000000000000000000000000000000000", // SETTLE_ALL currency: native ETH
+            "0000000000000000000000000000000000000000000000000de0b6b3a7640000", // maxAmount
+            "0000000000000000000000000000000000000000000000000000000000000060", // params[2]: length
+            "000000000000000000000000a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48", // TAKE currency: USDC
+            "000000000000000000000000000000000000000000000000000000000000dead", // recipient
+            "0000000000000000000000000000000000000000000000000000000000000000", // amount: OPEN_DELTA
+        ];
+        let mut expected = hex::decode("3593564c").unwrap();
+        for word in words {
+            expected.extend(hex::decode(word).unwrap());
+        }
+
+        assert_eq!(hex::encode(calldata), hex::encode(expected));
+    }
+}
@@ -0,0 +1,237 @@
+/*
+ * In-memory ring buffer of evaluated arbitrage opportunities
+ */
+
+use crate::models::{ArbitrageOpportunity, RecommendedAction};
+use chrono::{DateTime, Utc};
+use ethers::types::H256;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+pub struct OpportunityHistory {
+    capacity: usize,
+    entries: Mutex<VecDeque<ArbitrageOpportunity>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub action: Option<RecommendedAction>,
+    pub limit: Option<usize>,
+}
+
+impl OpportunityHistory {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::new(VecDeque::with_capacity(capacity.max(1))),
+        }
+    }
+
+    /// Appends an opportunity, evicting the oldest entry once the buffer is full.
+    pub fn record(&self, opportunity: ArbitrageOpportunity) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(opportunity);
+    }
+
+    /// Marks every opportunity computed on one of the `orphaned` blocks as invalidated,
+    /// returning how many were newly marked.
+    pub fn invalidate_blocks(&self, orphaned: &[H256]) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let mut invalidated = 0;
+        for opportunity in entries
+            .iter_mut()
+            .filter(|opportunity| !opportunity.invalidated)
+        {
+            if orphaned.contains(&opportunity.ethereum_block.hash)
+                || orphaned.contains(&opportunity.base_block.hash)
+            {
+                opportunity.invalidated = true;
+                invalidated += 1;
+            }
+        }
+        invalidated
+    }
+
+    /// Returns matching opportunities, newest first.
+    #[must_use]
+    pub fn query(&self, filter: &HistoryFilter) -> Vec<ArbitrageOpportunity> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .rev()
+            .filter(|opportunity| {
+                filter
+                    .from
+                    .is_none_or(|from| opportunity.timestamp_utc >= from)
+                    && filter.to.is_none_or(|to| opportunity.timestamp_utc <= to)
+                    && filter.action.as_ref().is_none_or(|action| {
+                        opportunity.arbitrage_summary.recommended_action == *action
+                    })
+            })
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        ArbitrageSummary, BlockRef, DexDetails, GasBreakdown, GasEstimateMethod, Venue,
+    };
+    use chrono::TimeDelta;
+    use rust_decimal::Decimal;
+
+    fn dex_details() -> DexDetails {
+        DexDetails {
+            effective_price_usd: Decimal::from(3000),
+            price_impact_percent: Decimal::ZERO,
+            estimated_gas_cost_usd: Decimal::ONE,
+            gas_estimate_method: GasEstimateMethod::TypicalConstant,
+            gas_breakdown: GasBreakdown {
+                base_fee_gwei: Decimal::ONE,
+                priority_fee_gwei: Decimal::ZERO,
+                gas_price_multiplier: Decimal::ONE,
+                gas_price_gwei: Decimal::ONE,
+                estimated_gas_units: 150_000,
+                buffered_gas_units: 150_000,
+                l1_fee_eth: Decimal::ZERO,
+                total_cost_eth: Decimal::ZERO,
+            },
+        }
+    }
+
+    fn block(number: u64, timestamp_utc: DateTime<Utc>) -> BlockRef {
+        BlockRef {
+            number,
+            hash: H256::from_low_u64_be(number),
+            timestamp_utc,
+        }
+    }
+
+    /// Opportunity evaluated `minutes` after a fixed start, at Ethereum block
+    /// `1000 + minutes` and Base block `2000 + minutes`.
+    fn opportunity(minutes: i64, action: RecommendedAction) -> ArbitrageOpportunity {
+        let timestamp_utc = DateTime::<Utc>::UNIX_EPOCH + TimeDelta::minutes(minutes);
+        let offset = u64::try_from(minutes).unwrap();
+        let summary = ArbitrageSummary {
+            buy_venue: Venue::UniswapV4,
+            sell_venue: Venue::Aerodrome,
+            round_trip_usdc_in: Decimal::from(3000),
+            round_trip_usdc_out: Decimal::from(3000),
+            potential_profit_usd: Decimal::ZERO,
+            total_gas_cost_usd: Decimal::ZERO,
+            net_profit_usd: Decimal::ZERO,
+            recommended_action: action,
+        };
+        ArbitrageOpportunity {
+            timestamp_utc,
+            trade_size_eth: Decimal::ONE,
+            reference_cex_price_usd: Decimal::from(3000),
+            reference_cex_quotes: Vec::new(),
+            reference_cex_spread_bps: Decimal::ZERO,
+            uniswap_v4_details: dex_details(),
+            aerodrome_details: dex_details(),
+            cex_details: None,
+            arbitrage_summary: summary.clone(),
+            routes: vec![summary],
+            ethereum_block: block(1000 + offset, timestamp_utc),
+            base_block: block(2000 + offset, timestamp_utc),
+            invalidated: false,
+            warnings: Vec::new(),
+        }
+    }
+
+    fn minutes(entries: &[ArbitrageOpportunity]) -> Vec<u64> {
+        entries
+            .iter()
+            .map(|opportunity| opportunity.ethereum_block.number - 1000)
+            .collect()
+    }
+
+    #[test]
+    fn evicts_the_oldest_entry_when_full() {
+        let history = OpportunityHistory::new(2);
+        for minute in 0..3 {
+            history.record(opportunity(minute, RecommendedAction::NoArbitrage));
+        }
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(minutes(&history.query(&HistoryFilter::default())), [2, 1]);
+    }
+
+    #[test]
+    fn filters_by_time_range_action_and_limit() {
+        let history = OpportunityHistory::new(10);
+        for minute in 0..6 {
+            let action = if minute % 2 == 0 {
+                RecommendedAction::ArbitrageDetected
+            } else {
+                RecommendedAction::NoArbitrage
+            };
+            history.record(opportunity(minute, action));
+        }
+        let at = |minutes| DateTime::<Utc>::UNIX_EPOCH + TimeDelta::minutes(minutes);
+
+        let in_range = history.query(&HistoryFilter {
+            from: Some(at(1)),
+            to: Some(at(4)),
+            ..HistoryFilter::default()
+        });
+        assert_eq!(minutes(&in_range), [4, 3, 2, 1]);
+
+        let detected = history.query(&HistoryFilter {
+            action: Some(RecommendedAction::ArbitrageDetected),
+            limit: Some(2),
+            ..HistoryFilter::default()
+        });
+        assert_eq!(minutes(&detected), [4, 2]);
+    }
+
+    #[test]
+    fn invalidates_entries_on_orphaned_blocks_once() {
+        let history = OpportunityHistory::new(10);
+        for minute in 0..3 {
+            history.record(opportunity(minute, RecommendedAction::NoArbitrage));
+        }
+        let orphaned = [H256::from_low_u64_be(1001), H256::from_low_u64_be(2002)];
+
+        assert_eq!(history.invalidate_blocks(&orphaned), 2);
+        assert_eq!(history.invalidate_blocks(&orphaned), 0);
+
+        let invalidated: Vec<_> = history
+            .query(&HistoryFilter::default())
+            .iter()
+            .map(|opportunity| opportunity.invalidated)
+            .collect();
+        assert_eq!(invalidated, [true, true, false]);
+    }
+
+    #[test]
+    fn zero_capacity_keeps_one_entry() {
+        let history = OpportunityHistory::new(0);
+        assert!(history.is_empty());
+
+        history.record(opportunity(0, RecommendedAction::NoArbitrage));
+        history.record(opportunity(1, RecommendedAction::NoArbitrage));
+
+        assert_eq!(minutes(&history.query(&HistoryFilter::default())), [1]);
+    }
+}
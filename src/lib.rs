@@ -10,7 +10,10 @@ pub mod api;
 pub mod cex;
 pub mod config;
 pub mod dex;
+pub mod history;
+pub mod metrics;
 pub mod models;
+pub mod retry;
 pub mod rpc;
 pub mod service;
 pub mod utils;
@@ -15,7 +15,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     info!("Starting Argus Arbitrage Monitoring Service");
 
-    let config = Config::from_env().map_err(|e| {
+    let config = Config::load().map_err(|e| {
         error!("Failed to load configuration: {}", e);
         e
     })?;
@@ -25,6 +25,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let arbitrage_service = ArbitrageService::new(config.clone()).await?;
     let arbitrage_service = Arc::new(RwLock::new(arbitrage_service));
 
+    if config.monitor.enabled {
+        ArbitrageService::spawn_monitor(arbitrage_service.clone(), config.monitor.clone());
+    }
+
     let api_state = api::ApiState {
         config: config.clone(),
         arbitrage_service,
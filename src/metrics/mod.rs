@@ -0,0 +1,238 @@
+/*
+ * Prometheus metrics registry and recording helpers
+ */
+
+use crate::models::{ArbitrageOpportunity, ArgusError, QuoteStatus};
+use prometheus::{
+    Encoder, Gauge, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts,
+    Registry, TextEncoder,
+};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+pub struct Metrics {
+    registry: Registry,
+    venue_effective_price_usd: GaugeVec,
+    cex_reference_price_usd: GaugeVec,
+    spread_bps: Gauge,
+    gas_cost_usd: GaugeVec,
+    net_profit_usd: Gauge,
+    rpc_request_duration_seconds: HistogramVec,
+    cex_request_duration_seconds: HistogramVec,
+    errors_total: IntCounterVec,
+    l1_fee_oracle_mismatches_total: IntCounterVec,
+    rpc_endpoint_up: IntGaugeVec,
+    retries_total: IntCounterVec,
+    reorgs_total: IntCounterVec,
+    guard_triggers_total: IntCounterVec,
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+const LATENCY_BUCKETS: &[f64] = &[0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+impl Metrics {
+    /// Process-wide metrics instance, registered on first use.
+    pub fn global() -> &'static Metrics {
+        METRICS.get_or_init(|| Self::new().expect("metric definitions are valid"))
+    }
+
+    fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new_custom(Some("argus".to_string()), None)?;
+
+        let venue_effective_price_usd = GaugeVec::new(
+            Opts::new(
+                "venue_effective_price_usd",
+                "Effective ETH price in USD of the last quoted leg per venue",
+            ),
+            &["venue"],
+        )?;
+        let cex_reference_price_usd = GaugeVec::new(
+            Opts::new("cex_reference_price_usd", "Last CEX reference ETH price"),
+            &["exchange"],
+        )?;
+        let spread_bps = Gauge::with_opts(Opts::new(
+            "spread_bps",
+            "Spread between the sell and buy venue effective prices in basis points",
+        ))?;
+        let gas_cost_usd = GaugeVec::new(
+            Opts::new("gas_cost_usd", "Estimated swap gas cost in USD per chain"),
+            &["chain"],
+        )?;
+        let net_profit_usd = Gauge::with_opts(Opts::new(
+            "net_profit_usd",
+            "Net round-trip profit after gas in USD",
+        ))?;
+        let rpc_request_duration_seconds = HistogramVec::new(
+            HistogramOpts::new("rpc_request_duration_seconds", "RPC request latency")
+                .buckets(LATENCY_BUCKETS.to_vec()),
+            &["chain_id", "method"],
+        )?;
+        let cex_request_duration_seconds = HistogramVec::new(
+            HistogramOpts::new("cex_request_duration_seconds", "CEX API request latency")
+                .buckets(LATENCY_BUCKETS.to_vec()),
+            &["exchange"],
+        )?;
+        let errors_total = IntCounterVec::new(
+            Opts::new("errors_total", "Errors by ArgusError variant"),
+            &["kind"],
+        )?;
+        let l1_fee_oracle_mismatches_total = IntCounterVec::new(
+            Opts::new(
+                "l1_fee_oracle_mismatches_total",
+                "Locally computed L1 data fees that differed from GasPriceOracle.getL1Fee",
+            ),
+            &["chain_id"],
+        )?;
+        let rpc_endpoint_up = IntGaugeVec::new(
+            Opts::new(
+                "rpc_endpoint_up",
+                "Whether an RPC endpoint is currently healthy",
+            ),
+            &["chain_id", "endpoint"],
+        )?;
+        let retries_total = IntCounterVec::new(
+            Opts::new("retries_total", "Retried RPC and CEX requests"),
+            &["client", "operation"],
+        )?;
+        let reorgs_total = IntCounterVec::new(
+            Opts::new("reorgs_total", "Chain reorgs detected"),
+            &["chain_id"],
+        )?;
+        let guard_triggers_total = IntCounterVec::new(
+            Opts::new(
+                "guard_triggers_total",
+                "Sanity guards tripped by evaluations",
+            ),
+            &["guard"],
+        )?;
+
+        registry.register(Box::new(venue_effective_price_usd.clone()))?;
+        registry.register(Box::new(cex_reference_price_usd.clone()))?;
+        registry.register(Box::new(spread_bps.clone()))?;
+        registry.register(Box::new(gas_cost_usd.clone()))?;
+        registry.register(Box::new(net_profit_usd.clone()))?;
+        registry.register(Box::new(rpc_request_duration_seconds.clone()))?;
+        registry.register(Box::new(cex_request_duration_seconds.clone()))?;
+        registry.register(Box::new(errors_total.clone()))?;
+        registry.register(Box::new(l1_fee_oracle_mismatches_total.clone()))?;
+        registry.register(Box::new(rpc_endpoint_up.clone()))?;
+        registry.register(Box::new(retries_total.clone()))?;
+        registry.register(Box::new(reorgs_total.clone()))?;
+        registry.register(Box::new(guard_triggers_total.clone()))?;
+
+        Ok(Self {
+            registry,
+            venue_effective_price_usd,
+            cex_reference_price_usd,
+            spread_bps,
+            gas_cost_usd,
+            net_profit_usd,
+            rpc_request_duration_seconds,
+            cex_request_duration_seconds,
+            errors_total,
+            l1_fee_oracle_mismatches_total,
+            rpc_endpoint_up,
+            retries_total,
+            reorgs_total,
+            guard_triggers_total,
+        })
+    }
+
+    pub fn record_opportunity(&self, opportunity: &ArbitrageOpportunity, exchange: &str) {
+        let summary = &opportunity.arbitrage_summary;
+
+        self.venue_effective_price_usd
+            .with_label_values(&["uniswap_v4"])
+            .set(to_f64(opportunity.uniswap_v4_details.effective_price_usd));
+        self.venue_effective_price_usd
+            .with_label_values(&["aerodrome"])
+            .set(to_f64(opportunity.aerodrome_details.effective_price_usd));
+        self.cex_reference_price_usd
+            .with_label_values(&[exchange])
+            .set(to_f64(opportunity.reference_cex_price_usd));
+        for quote in &opportunity.reference_cex_quotes {
+            if quote.status == QuoteStatus::Used {
+                self.cex_reference_price_usd
+                    .with_label_values(&[&quote.quote.exchange])
+                    .set(to_f64(quote.quote.price));
+            }
+        }
+        self.gas_cost_usd
+            .with_label_values(&["ethereum"])
+            .set(to_f64(
+                opportunity.uniswap_v4_details.estimated_gas_cost_usd,
+            ));
+        self.gas_cost_usd
+            .with_label_values(&["base"])
+            .set(to_f64(opportunity.aerodrome_details.estimated_gas_cost_usd));
+        self.net_profit_usd.set(to_f64(summary.net_profit_usd));
+        for warning in &opportunity.warnings {
+            self.guard_triggers_total
+                .with_label_values(&[warning.guard.as_str()])
+                .inc();
+        }
+
+        if !summary.round_trip_usdc_in.is_zero() {
+            let spread = (summary.round_trip_usdc_out - summary.round_trip_usdc_in)
+                / summary.round_trip_usdc_in
+                * Decimal::from(10_000);
+            self.spread_bps.set(to_f64(spread));
+        }
+    }
+
+    pub fn observe_rpc_latency(&self, chain_id: u64, method: &str, started: Instant) {
+        self.rpc_request_duration_seconds
+            .with_label_values(&[&chain_id.to_string(), method])
+            .observe(started.elapsed().as_secs_f64());
+    }
+
+    pub fn observe_cex_latency(&self, exchange: &str, started: Instant) {
+        self.cex_request_duration_seconds
+            .with_label_values(&[exchange])
+            .observe(started.elapsed().as_secs_f64());
+    }
+
+    pub fn record_l1_fee_mismatch(&self, chain_id: u64) {
+        self.l1_fee_oracle_mismatches_total
+            .with_label_values(&[&chain_id.to_string()])
+            .inc();
+    }
+
+    pub fn set_rpc_endpoint_health(&self, chain_id: u64, endpoint: &str, healthy: bool) {
+        self.rpc_endpoint_up
+            .with_label_values(&[&chain_id.to_string(), endpoint])
+            .set(i64::from(healthy));
+    }
+
+    pub fn record_retry(&self, client: &str, operation: &str) {
+        self.retries_total
+            .with_label_values(&[client, operation])
+            .inc();
+    }
+
+    pub fn record_reorg(&self, chain_id: u64) {
+        self.reorgs_total
+            .with_label_values(&[&chain_id.to_string()])
+            .inc();
+    }
+
+    pub fn record_error(&self, error: &ArgusError) {
+        self.errors_total.with_label_values(&[error.kind()]).inc();
+    }
+
+    /// Renders all registered metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buffer) {
+            tracing::error!("Failed to encode metrics: {}", e);
+        }
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
@@ -3,6 +3,7 @@
  */
 
 use chrono::{DateTime, Utc};
+use ethers::types::{Block, H256};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -12,9 +13,60 @@ pub struct ArbitrageOpportunity {
     pub timestamp_utc: DateTime<Utc>,
     pub trade_size_eth: Decimal,
     pub reference_cex_price_usd: Decimal,
+    /// Every exchange quote behind `reference_cex_price_usd`, including dropped ones
+    pub reference_cex_quotes: Vec<CexQuote>,
+    /// Spread between the highest and lowest used exchange quote
+    pub reference_cex_spread_bps: Decimal,
     pub uniswap_v4_details: DexDetails,
     pub aerodrome_details: DexDetails,
+    /// Order book figures behind the CEX routes; absent when the book could not be fetched
+    pub cex_details: Option<CexDetails>,
+    /// Most profitable route in `routes`
     pub arbitrage_summary: ArbitrageSummary,
+    /// Every route evaluated, across all venue pairs, most profitable first
+    pub routes: Vec<ArbitrageSummary>,
+    /// Ethereum block the Uniswap V4 quotes and gas costs were read at
+    pub ethereum_block: BlockRef,
+    /// Base block the Aerodrome quotes and gas costs were read at
+    pub base_block: BlockRef,
+    /// Set once either block has been orphaned by a reorg; the figures no longer describe
+    /// the canonical chain
+    pub invalidated: bool,
+    /// Sanity guards the evaluation tripped, empty when its inputs looked plausible
+    pub warnings: Vec<GuardWarning>,
+}
+
+/// A block that on-chain reads were pinned to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockRef {
+    pub number: u64,
+    pub hash: H256,
+    pub timestamp_utc: DateTime<Utc>,
+}
+
+impl TryFrom<&Block<H256>> for BlockRef {
+    type Error = ArgusError;
+
+    fn try_from(block: &Block<H256>) -> Result<Self> {
+        let (Some(number), Some(hash)) = (block.number, block.hash) else {
+            return Err(ArgusError::RpcError(
+                "Block is pending and has no number or hash".to_string(),
+            ));
+        };
+
+        let timestamp_utc = i64::try_from(block.timestamp)
+            .ok()
+            .and_then(|secs| DateTime::from_timestamp(secs, 0))
+            .ok_or_else(|| {
+                ArgusError::RpcError(format!("Invalid block timestamp: {}", block.timestamp))
+            })?;
+
+        Ok(Self {
+            number: number.as_u64(),
+            hash,
+            timestamp_utc,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,10 +74,53 @@ pub struct DexDetails {
     pub effective_price_usd: Decimal,
     pub price_impact_percent: Decimal,
     pub estimated_gas_cost_usd: Decimal,
+    pub gas_estimate_method: GasEstimateMethod,
+    pub gas_breakdown: GasBreakdown,
+}
+
+/// How the gas cost of a swap was put together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GasBreakdown {
+    pub base_fee_gwei: Decimal,
+    pub priority_fee_gwei: Decimal,
+    /// Applied to base fee plus priority fee
+    pub gas_price_multiplier: Decimal,
+    /// `(base_fee_gwei + priority_fee_gwei) * gas_price_multiplier`
+    pub gas_price_gwei: Decimal,
+    /// Gas units as estimated
+    pub estimated_gas_units: u64,
+    /// Gas units paid for, after the configured buffer
+    pub buffered_gas_units: u64,
+    /// L1 data fee of a swap on an L2; zero on Ethereum
+    pub l1_fee_eth: Decimal,
+    /// Execution cost of the buffered units plus the L1 data fee
+    pub total_cost_eth: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CexDetails {
+    pub exchange: String,
+    pub best_bid_usd: Decimal,
+    pub best_ask_usd: Decimal,
+    pub taker_fee_bps: u32,
+}
+
+/// How the gas units behind `estimated_gas_cost_usd` were obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GasEstimateMethod {
+    /// `eth_estimateGas` against the router calldata with state overrides
+    EthEstimateGas,
+    /// Typical swap gas units for the chain
+    TypicalConstant,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArbitrageSummary {
+    pub buy_venue: Venue,
+    pub sell_venue: Venue,
+    pub round_trip_usdc_in: Decimal,
+    pub round_trip_usdc_out: Decimal,
     pub potential_profit_usd: Decimal,
     pub total_gas_cost_usd: Decimal,
     pub net_profit_usd: Decimal,
@@ -33,10 +128,90 @@ pub struct ArbitrageSummary {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeSizeOptimization {
+    pub timestamp_utc: DateTime<Utc>,
+    pub reference_cex_price_usd: Decimal,
+    pub total_gas_cost_usd: Decimal,
+    pub optimal: ProfitPoint,
+    pub recommended_action: RecommendedAction,
+    pub profit_curve: Vec<ProfitPoint>,
+    pub ethereum_block: BlockRef,
+    pub base_block: BlockRef,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfitPoint {
+    pub trade_size_eth: Decimal,
+    pub buy_venue: Venue,
+    pub sell_venue: Venue,
+    pub potential_profit_usd: Decimal,
+    pub net_profit_usd: Decimal,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Venue {
+    UniswapV4,
+    Aerodrome,
+    /// The configured centralized exchange
+    Cex,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum RecommendedAction {
     ArbitrageDetected,
     NoArbitrage,
+    /// Profitable, but a sanity guard failed on one of the route's venues; see `warnings`
+    RejectedByGuard,
+}
+
+impl std::str::FromStr for RecommendedAction {
+    type Err = ArgusError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_uppercase().as_str() {
+            "ARBITRAGE_DETECTED" => Ok(RecommendedAction::ArbitrageDetected),
+            "NO_ARBITRAGE" => Ok(RecommendedAction::NoArbitrage),
+            "REJECTED_BY_GUARD" => Ok(RecommendedAction::RejectedByGuard),
+            _ => Err(ArgusError::ConfigError(format!(
+                "Unknown recommended action: {s}"
+            ))),
+        }
+    }
+}
+
+/// Sanity check on the inputs of an evaluation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GuardKind {
+    /// A CEX quote behind the reference price is older than allowed
+    StaleCexQuote,
+    /// A DEX effective price is implausibly far from the CEX reference
+    DexPriceDeviation,
+    /// A pool holds less in-range liquidity than the configured floor
+    LowPoolLiquidity,
+}
+
+impl GuardKind {
+    /// Stable, label-friendly name of the guard.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GuardKind::StaleCexQuote => "stale_cex_quote",
+            GuardKind::DexPriceDeviation => "dex_price_deviation",
+            GuardKind::LowPoolLiquidity => "low_pool_liquidity",
+        }
+    }
+}
+
+/// A guard that an evaluation tripped, and why.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardWarning {
+    pub guard: GuardKind,
+    /// Venue whose figures tripped the guard; absent when it concerns every route
+    pub venue: Option<Venue>,
+    pub message: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,9 +219,177 @@ pub struct CexPrice {
     pub exchange: String,
     pub pair: String,
     pub price: Decimal,
+    /// Base asset traded over the last 24 hours, when the exchange reports it
+    pub volume_24h: Option<Decimal>,
+    /// When the exchange produced the price, or when it was fetched if the exchange
+    /// does not say
     pub timestamp: DateTime<Utc>,
 }
 
+/// How several exchanges' quotes are combined into one reference price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PriceAggregation {
+    Median,
+    /// Weighted by each exchange's 24 hour volume
+    VolumeWeighted,
+}
+
+impl std::str::FromStr for PriceAggregation {
+    type Err = ArgusError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "median" => Ok(PriceAggregation::Median),
+            "volume_weighted" | "vwap" => Ok(PriceAggregation::VolumeWeighted),
+            _ => Err(ArgusError::ConfigError(format!(
+                "Unknown price aggregation: {s}"
+            ))),
+        }
+    }
+}
+
+/// Whether an exchange quote went into the reference price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStatus {
+    Used,
+    /// Older than the maximum quote age
+    Stale,
+    /// Too far from the median of the fresh quotes
+    Outlier,
+    /// Zero or negative price
+    Rejected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CexQuote {
+    #[serde(flatten)]
+    pub quote: CexPrice,
+    pub status: QuoteStatus,
+}
+
+/// Reference price and the exchange quotes it was derived from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferencePrice {
+    pub pair: String,
+    pub price: Decimal,
+    /// `None` when the price is a single exchange's quote
+    pub aggregation: Option<PriceAggregation>,
+    pub quotes: Vec<CexQuote>,
+    /// Spread between the highest and lowest used quote, relative to `price`
+    pub spread_bps: Decimal,
+}
+
+impl From<CexPrice> for ReferencePrice {
+    fn from(quote: CexPrice) -> Self {
+        Self {
+            pair: quote.pair.clone(),
+            price: quote.price,
+            aggregation: None,
+            quotes: vec![CexQuote {
+                quote,
+                status: QuoteStatus::Used,
+            }],
+            spread_bps: Decimal::ZERO,
+        }
+    }
+}
+
+/// One price level of an order book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderBookLevel {
+    pub price: Decimal,
+    /// Base asset resting at `price`
+    pub quantity: Decimal,
+}
+
+/// Top levels of a CEX order book, best price first on each side.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderBook {
+    pub exchange: String,
+    pub pair: String,
+    /// Highest price first
+    pub bids: Vec<OrderBookLevel>,
+    /// Lowest price first
+    pub asks: Vec<OrderBookLevel>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Direction of a market order against an order book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderSide {
+    /// Buys the base asset, filling against the asks
+    Buy,
+    /// Sells the base asset, filling against the bids
+    Sell,
+}
+
+/// Price of filling a market order of `size` base units against an order book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VwapFill {
+    pub side: OrderSide,
+    pub size: Decimal,
+    /// Volume-weighted average price over the levels consumed
+    pub average_price: Decimal,
+    /// Price of the last level consumed
+    pub worst_price: Decimal,
+}
+
+impl OrderBook {
+    #[must_use]
+    pub fn best_bid(&self) -> Option<Decimal> {
+        self.bids.first().map(|level| level.price)
+    }
+
+    #[must_use]
+    pub fn best_ask(&self) -> Option<Decimal> {
+        self.asks.first().map(|level| level.price)
+    }
+
+    /// Walks the levels `side` fills against until `amount` base units are filled.
+    /// Fails when `amount` is not positive or exceeds the depth of the fetched levels.
+    pub fn vwap(&self, side: OrderSide, amount: Decimal) -> Result<VwapFill> {
+        if amount <= Decimal::ZERO {
+            return Err(ArgusError::CalculationError(format!(
+                "VWAP size must be positive, got {amount}"
+            )));
+        }
+
+        let (levels, side_name) = match side {
+            OrderSide::Buy => (&self.asks, "asks"),
+            OrderSide::Sell => (&self.bids, "bids"),
+        };
+
+        let mut remaining = amount;
+        let mut notional = Decimal::ZERO;
+        for level in levels {
+            let filled = remaining.min(level.quantity);
+            notional += filled * level.price;
+            remaining -= filled;
+
+            if remaining.is_zero() {
+                return Ok(VwapFill {
+                    side,
+                    size: amount,
+                    average_price: notional / amount,
+                    worst_price: level.price,
+                });
+            }
+        }
+
+        Err(ArgusError::CalculationError(format!(
+            "{} {} {} hold only {} of the {} requested",
+            self.exchange,
+            self.pair,
+            side_name,
+            amount - remaining,
+            amount
+        )))
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ArgusError {
     #[error("RPC error: {0}")]
@@ -74,4 +417,88 @@ pub enum ArgusError {
     Unknown(String),
 }
 
+impl ArgusError {
+    /// Stable, label-friendly name of the error variant.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ArgusError::RpcError(_) => "rpc",
+            ArgusError::CexApiError(_) => "cex_api",
+            ArgusError::ContractError(_) => "contract",
+            ArgusError::CalculationError(_) => "calculation",
+            ArgusError::ConfigError(_) => "config",
+            ArgusError::NetworkError(_) => "network",
+            ArgusError::SerializationError(_) => "serialization",
+            ArgusError::Unknown(_) => "unknown",
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, ArgusError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn level(price: &str, quantity: &str) -> OrderBookLevel {
+        OrderBookLevel {
+            price: dec(price),
+            quantity: dec(quantity),
+        }
+    }
+
+    fn book() -> OrderBook {
+        OrderBook {
+            exchange: "Coinbase".to_string(),
+            pair: "ETH/USDC".to_string(),
+            bids: vec![level("2999", "1"), level("2998", "2"), level("2990", "5")],
+            asks: vec![level("3001", "1"), level("3002", "2"), level("3010", "5")],
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn vwap_exact_fill_of_whole_levels() {
+        let fill = book().vwap(OrderSide::Buy, dec("3")).unwrap();
+        assert_eq!(fill.size, dec("3"));
+        assert_eq!(fill.average_price, (dec("3001") + dec("6004")) / dec("3"));
+        assert_eq!(fill.worst_price, dec("3002"));
+    }
+
+    #[test]
+    fn vwap_partial_last_level() {
+        let fill = book().vwap(OrderSide::Sell, dec("4")).unwrap();
+        assert_eq!(
+            fill.average_price,
+            (dec("2999") + dec("5996") + dec("2990")) / dec("4")
+        );
+        assert_eq!(fill.worst_price, dec("2990"));
+        assert_eq!(fill.side, OrderSide::Sell);
+    }
+
+    #[test]
+    fn vwap_within_top_level() {
+        let fill = book().vwap(OrderSide::Buy, dec("0.5")).unwrap();
+        assert_eq!(fill.average_price, dec("3001"));
+        assert_eq!(fill.worst_price, dec("3001"));
+    }
+
+    #[test]
+    fn vwap_fails_beyond_book_depth() {
+        let error = book().vwap(OrderSide::Buy, dec("8.5")).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("asks hold only 8.0 of the 8.5 requested"));
+    }
+
+    #[test]
+    fn vwap_rejects_non_positive_amounts() {
+        assert!(book().vwap(OrderSide::Buy, Decimal::ZERO).is_err());
+        assert!(book().vwap(OrderSide::Sell, dec("-1")).is_err());
+    }
+}
@@ -0,0 +1,239 @@
+/*
+ * Shared retry policy with exponential backoff and jitter for RPC and CEX requests
+ */
+
+use crate::config::RetryConfig;
+use crate::metrics::Metrics;
+use crate::models::ArgusError;
+use rand::Rng;
+use std::fmt::Display;
+use std::future::Future;
+use std::time::Duration;
+use tracing::warn;
+
+/// Errors that can tell whether repeating the request might succeed.
+pub trait Retryable {
+    fn is_retryable(&self) -> bool;
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first one
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Timeout of a single request, enforced by the transport making it
+    pub timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(2),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+impl From<&RetryConfig> for RetryPolicy {
+    fn from(config: &RetryConfig) -> Self {
+        Self {
+            max_attempts: config.max_attempts.max(1),
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.max_backoff_ms),
+            timeout: Duration::from_millis(config.request_timeout_ms),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Runs `request` until it succeeds, fails with a non-retryable error or runs out of
+    /// attempts. Every retry is logged and counted under `client` and `operation`.
+    pub async fn retry<T, E, F, Fut>(
+        &self,
+        client: &str,
+        operation: &str,
+        mut request: F,
+    ) -> Result<T, E>
+    where
+        E: Retryable + Display,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match request().await {
+                Err(e) if e.is_retryable() && attempt < self.max_attempts => {
+                    let backoff = self.backoff(attempt);
+                    warn!(
+                        "{} {} failed (attempt {}/{}), retrying in {}ms: {}",
+                        client,
+                        operation,
+                        attempt,
+                        self.max_attempts,
+                        backoff.as_millis(),
+                        e
+                    );
+                    Metrics::global().record_retry(client, operation);
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                Err(e) if attempt > 1 => {
+                    warn!(
+                        "{} {} failed after {} attempts: {}",
+                        client, operation, attempt, e
+                    );
+                    return Err(e);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Exponential backoff capped at `max_backoff`, with full jitter so concurrent callers
+    /// do not retry in lockstep.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(self.max_backoff);
+        exponential.mul_f64(rand::thread_rng().gen_range(0.0..=1.0))
+    }
+}
+
+impl Retryable for ArgusError {
+    /// Connection failures, timeouts, rate limits and server errors are transient; API
+    /// and parsing errors are not.
+    fn is_retryable(&self) -> bool {
+        match self {
+            ArgusError::NetworkError(e) => match e.status() {
+                Some(status) => status.is_server_error() || status.as_u16() == 429,
+                None => e.is_timeout() || e.is_connect() || e.is_request(),
+            },
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[derive(Debug)]
+    struct TestError {
+        retryable: bool,
+    }
+
+    impl Retryable for TestError {
+        fn is_retryable(&self) -> bool {
+            self.retryable
+        }
+    }
+
+    impl Display for TestError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "retryable: {}", self.retryable)
+        }
+    }
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+            timeout: Duration::from_secs(1),
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(300),
+            ..RetryPolicy::default()
+        };
+
+        for _ in 0..100 {
+            assert!(policy.backoff(1) <= Duration::from_millis(100));
+            assert!(policy.backoff(2) <= Duration::from_millis(200));
+            assert!(policy.backoff(3) <= Duration::from_millis(300));
+            assert!(policy.backoff(40) <= Duration::from_millis(300));
+        }
+    }
+
+    #[test]
+    fn config_allows_at_least_one_attempt() {
+        let policy = RetryPolicy::from(&RetryConfig {
+            max_attempts: 0,
+            initial_backoff_ms: 50,
+            max_backoff_ms: 500,
+            request_timeout_ms: 1000,
+        });
+
+        assert_eq!(policy.max_attempts, 1);
+        assert_eq!(policy.initial_backoff, Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors_until_attempts_run_out() {
+        let calls = Cell::new(0);
+
+        let result: Result<(), _> = policy(3)
+            .retry("test", "op", || {
+                calls.set(calls.get() + 1);
+                async { Err(TestError { retryable: true }) }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[tokio::test]
+    async fn stops_at_the_first_permanent_error() {
+        let calls = Cell::new(0);
+
+        let result: Result<(), _> = policy(3)
+            .retry("test", "op", || {
+                calls.set(calls.get() + 1);
+                async { Err(TestError { retryable: false }) }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn returns_the_first_success() {
+        let calls = Cell::new(0);
+
+        let result = policy(5)
+            .retry("test", "op", || {
+                calls.set(calls.get() + 1);
+                let attempt = calls.get();
+                async move {
+                    if attempt < 3 {
+                        Err(TestError { retryable: true })
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn connection_failures_are_retryable_and_api_errors_are_not() {
+        let refused = reqwest::get("http://127.0.0.1:1").await.unwrap_err();
+
+        assert!(ArgusError::NetworkError(refused).is_retryable());
+        assert!(!ArgusError::CexApiError("invalid symbol".to_string()).is_retryable());
+        assert!(!ArgusError::RpcError("execution reverted".to_string()).is_retryable());
+    }
+}
@@ -0,0 +1,108 @@
+/*
+ * A single RPC endpoint of a chain, with its priority and health state
+ */
+
+use super::transport::Transport;
+use crate::metrics::Metrics;
+use crate::models::{ArgusError, Result};
+use crate::retry::Retryable;
+use ethers::providers::{Middleware, Provider, ProviderError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+pub struct Endpoint {
+    /// Host of the endpoint URL; safe to log since it carries no path or credentials
+    pub label: String,
+    /// Lower values are preferred
+    pub priority: u32,
+    pub provider: Arc<Provider<Transport>>,
+    chain_id: u64,
+    healthy: AtomicBool,
+}
+
+impl Endpoint {
+    /// Connects to `url` and checks that it serves `chain_id`.
+    pub async fn connect(url: &str, priority: u32, chain_id: u64) -> Result<Self> {
+        let label = host_label(url);
+        let provider = Provider::new(Transport::connect(url).await?);
+
+        let chain = provider.get_chainid().await.map_err(|e| {
+            ArgusError::RpcError(format!("Failed to get chain ID from {label}: {e}"))
+        })?;
+
+        if chain.as_u64() != chain_id {
+            return Err(ArgusError::ConfigError(format!(
+                "Chain ID mismatch on {}: expected {}, got {}",
+                label,
+                chain_id,
+                chain.as_u64()
+            )));
+        }
+
+        let endpoint = Self {
+            label,
+            priority,
+            provider: Arc::new(provider),
+            chain_id,
+            healthy: AtomicBool::new(true),
+        };
+        endpoint.publish_health(true);
+        Ok(endpoint)
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    pub fn mark_healthy(&self) {
+        if !self.healthy.swap(true, Ordering::Relaxed) {
+            info!(
+                "RPC endpoint {} on chain {} is healthy again",
+                self.label, self.chain_id
+            );
+            self.publish_health(true);
+        }
+    }
+
+    pub fn mark_unhealthy(&self, reason: &str) {
+        if self.healthy.swap(false, Ordering::Relaxed) {
+            warn!(
+                "RPC endpoint {} on chain {} marked unhealthy: {}",
+                self.label, self.chain_id, reason
+            );
+            self.publish_health(false);
+        }
+    }
+
+    fn publish_health(&self, healthy: bool) {
+        Metrics::global().set_rpc_endpoint_health(self.chain_id, &self.label, healthy);
+    }
+}
+
+/// Whether `error` means the endpoint itself misbehaved (transport failure, timeout,
+/// garbage response) rather than the node answering with a JSON-RPC error such as a revert.
+pub fn is_endpoint_failure(error: &ProviderError) -> bool {
+    match error {
+        ProviderError::JsonRpcClientError(e) => !e.is_error_response(),
+        _ => true,
+    }
+}
+
+impl Retryable for ProviderError {
+    fn is_retryable(&self) -> bool {
+        is_endpoint_failure(self)
+    }
+}
+
+pub(crate) fn host_label(url: &str) -> String {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = without_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or(without_scheme);
+    authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host)
+        .to_string()
+}
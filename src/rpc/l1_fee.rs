@@ -0,0 +1,231 @@
+/*
+ * OP Stack L1 data fee formulas (Ecotone and Fjord), mirroring GasPriceOracle
+ */
+
+use ethers::types::U256;
+
+/// Scalars are fixed-point with 6 decimals.
+const DECIMALS: u32 = 6;
+
+// Fjord linear regression from FastLZ size to estimated Brotli size, scaled by 1e6
+const COST_INTERCEPT: i64 = -42_585_600;
+const COST_FASTLZ_COEF: i64 = 836_500;
+const MIN_TRANSACTION_SIZE: i64 = 100;
+
+/// Bytes added for the signature of an unsigned transaction.
+const SIGNATURE_OVERHEAD: u32 = 68;
+
+/// `GasPriceOracle` inputs for one L2 block.
+#[derive(Debug, Clone, Copy)]
+pub struct L1FeeParams {
+    pub block_number: u64,
+    pub l1_base_fee: U256,
+    pub blob_base_fee: U256,
+    pub base_fee_scalar: u32,
+    pub blob_base_fee_scalar: u32,
+    pub is_fjord: bool,
+}
+
+impl L1FeeParams {
+    /// L1 data fee in wei for an unsigned, RLP-encoded transaction, using whichever formula
+    /// is active, exactly as `GasPriceOracle.getL1Fee` computes it.
+    #[must_use]
+    pub fn l1_fee(&self, unsigned_tx: &[u8]) -> U256 {
+        if self.is_fjord {
+            self.fjord_l1_fee(unsigned_tx)
+        } else {
+            self.ecotone_l1_fee(unsigned_tx)
+        }
+    }
+
+    /// Ecotone: calldata gas priced at the scaled L1 base fee plus scaled blob base fee.
+    #[must_use]
+    pub fn ecotone_l1_fee(&self, unsigned_tx: &[u8]) -> U256 {
+        let zeros: u64 = unsigned_tx.iter().map(|byte| u64::from(*byte == 0)).sum();
+        let non_zeros = unsigned_tx.len() as u64 - zeros;
+        let l1_gas_used = zeros * 4 + non_zeros * 16 + u64::from(SIGNATURE_OVERHEAD) * 16;
+
+        U256::from(l1_gas_used) * self.scaled_fee()
+            / (U256::from(16) * U256::exp10(DECIMALS as usize))
+    }
+
+    /// Fjord: FastLZ-compressed size mapped to an estimated Brotli size by linear regression.
+    #[must_use]
+    pub fn fjord_l1_fee(&self, unsigned_tx: &[u8]) -> U256 {
+        let fast_lz_size = i64::from(flz_compress_len(unsigned_tx) + SIGNATURE_OVERHEAD);
+        let estimated_size = (COST_INTERCEPT + COST_FASTLZ_COEF * fast_lz_size)
+            .max(MIN_TRANSACTION_SIZE * 1_000_000);
+
+        U256::from(estimated_size) * self.scaled_fee() / U256::exp10(2 * DECIMALS as usize)
+    }
+
+    fn scaled_fee(&self) -> U256 {
+        U256::from(self.base_fee_scalar) * 16 * self.l1_base_fee
+            + U256::from(self.blob_base_fee_scalar) * self.blob_base_fee
+    }
+}
+
+/// Length of the `FastLZ` (level 1) compression of `input`, as computed by Solady's
+/// `LibZip.flzCompress` and op-geth's `FlzCompressLen`.
+#[must_use]
+pub fn flz_compress_len(input: &[u8]) -> u32 {
+    fn u24(input: &[u8], i: usize) -> u32 {
+        u32::from(input[i]) | u32::from(input[i + 1]) << 8 | u32::from(input[i + 2]) << 16
+    }
+
+    fn hash(value: u32) -> usize {
+        (value.wrapping_mul(2_654_435_769) >> 19) as usize & 0x1fff
+    }
+
+    fn literals(n: &mut usize, run: usize) {
+        let remainder = run % 0x20;
+        *n += 0x21 * (run / 0x20);
+        if remainder != 0 {
+            *n += remainder + 1;
+        }
+    }
+
+    fn matched(n: &mut usize, length: usize) {
+        let length = length - 1;
+        *n += 3 * (length / 262);
+        *n += if length % 262 >= 6 { 3 } else { 2 };
+    }
+
+    // Counts the matching bytes of `input[p..]` and `input[q..end]`; like the reference
+    // implementation it over-counts by one on a mismatch
+    fn compare(input: &[u8], p: usize, q: usize, end: usize) -> usize {
+        let mut limit = end.saturating_sub(q);
+        let mut length = 0;
+        while length < limit {
+            if input[p + length] != input[q + length] {
+                limit = 0;
+            }
+            length += 1;
+        }
+        length
+    }
+
+    let mut n = 0;
+    let mut table = vec![0usize; 8192];
+    let mut anchor = 0;
+    let ip_limit = input.len().saturating_sub(13);
+
+    let mut ip = anchor + 2;
+    while ip < ip_limit {
+        let mut reference;
+        loop {
+            let sequence = u24(input, ip);
+            let h = hash(sequence);
+            reference = table[h];
+            table[h] = ip;
+            let distance = ip - reference;
+            if ip >= ip_limit {
+                break;
+            }
+            ip += 1;
+            if distance <= 0x1fff && sequence == u24(input, reference) {
+                break;
+            }
+        }
+        if ip >= ip_limit {
+            break;
+        }
+
+        ip -= 1;
+        if ip > anchor {
+            literals(&mut n, ip - anchor);
+        }
+
+        let length = compare(input, reference + 3, ip + 3, ip_limit + 9);
+        matched(&mut n, length);
+
+        ip += length;
+        for _ in 0..2 {
+            table[hash(u24(input, ip))] = ip;
+            ip += 1;
+        }
+        anchor = ip;
+    }
+
+    literals(&mut n, input.len() - anchor);
+    // Transactions are far below 4 GiB, so this only saturates on absurd input
+    u32::try_from(n).unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // op-geth's rollup cost test parameters: L1 base fee 1000e6, blob base fee 10e6 and
+    // scalars 2 and 3, so the scaled fee is 2 * 16 * 1000e6 + 3 * 10e6 = 32_030e6
+    const PARAMS: L1FeeParams = L1FeeParams {
+        block_number: 0,
+        l1_base_fee: U256([1_000_000_000, 0, 0, 0]),
+        blob_base_fee: U256([10_000_000, 0, 0, 0]),
+        base_fee_scalar: 2,
+        blob_base_fee_scalar: 3,
+        is_fjord: true,
+    };
+
+    /// Deterministic incompressible bytes.
+    fn noise(len: usize) -> Vec<u8> {
+        let mut state = 1u64;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1_442_695_040_888_963_407);
+                state.to_be_bytes()[0]
+            })
+            .collect()
+    }
+
+    // Expected lengths are those of the full FastLZ level 1 output of `LibZip.flzCompress`
+    #[test]
+    fn flz_compress_len_matches_fastlz() {
+        assert_eq!(flz_compress_len(&[]), 0);
+        assert_eq!(flz_compress_len(&[1]), 2);
+        assert_eq!(flz_compress_len(&(1..=16).collect::<Vec<u8>>()), 17);
+        assert_eq!(flz_compress_len(&[1; 100]), 12);
+        assert_eq!(flz_compress_len(&[0xab; 1000]), 21);
+        assert_eq!(
+            flz_compress_len(&b"The quick brown fox jumps over the lazy dog. ".repeat(8)),
+            59
+        );
+        assert_eq!(flz_compress_len(&noise(256)), 264);
+
+        let mut mixed = vec![0; 4];
+        mixed.extend(&noise(100));
+        mixed.extend([0; 200]);
+        mixed.extend(&noise(100));
+        assert_eq!(flz_compress_len(&mixed), 122);
+    }
+
+    #[test]
+    fn fjord_fee_has_a_minimum_size() {
+        // 100e6 * (2 * 16 * 1000e6 + 3 * 10e6) / 1e12, op-geth's minimal Fjord fee
+        assert_eq!(PARAMS.fjord_l1_fee(&[]), U256::from(3_203_000));
+        assert_eq!(PARAMS.fjord_l1_fee(&[0xab; 1000]), U256::from(3_203_000));
+    }
+
+    #[test]
+    fn fjord_fee_scales_with_compressed_size() {
+        // (-42_585_600 + 836_500 * (264 + 68)) * 32_030e6 / 1e12
+        assert_eq!(PARAMS.fjord_l1_fee(&noise(256)), U256::from(7_531_290));
+        assert_eq!(PARAMS.l1_fee(&noise(256)), U256::from(7_531_290));
+    }
+
+    #[test]
+    fn ecotone_fee_prices_calldata_gas() {
+        let params = L1FeeParams {
+            is_fjord: false,
+            ..PARAMS
+        };
+
+        // The signature overhead alone: 68 * 16 = 1088 gas at 32_030e6 / 16e6 per gas
+        assert_eq!(params.ecotone_l1_fee(&[]), U256::from(2_178_040));
+        // 3 zero and 253 non-zero bytes: 3 * 4 + 253 * 16 + 1088 = 5148 gas
+        assert_eq!(params.ecotone_l1_fee(&noise(256)), U256::from(10_305_652));
+        assert_eq!(params.l1_fee(&noise(256)), U256::from(10_305_652));
+    }
+}
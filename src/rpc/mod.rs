@@ -2,43 +2,325 @@
  * RPC client module for interacting with Ethereum and Base chains
  */
 
+mod endpoint;
+mod l1_fee;
+mod multicall;
+mod reorg;
+mod transport;
+
+pub(crate) use endpoint::host_label;
+pub use l1_fee::{flz_compress_len, L1FeeParams};
+pub use multicall::{Call3, Call3Result, MulticallResult, MULTICALL3_ADDRESS};
+pub use reorg::{BlockHistory, ReorgEvent, TRACKED_BLOCKS};
+pub use transport::{Transport, TransportError};
+
+use crate::config::{ChainConfig, L1FeeModel};
+use crate::metrics::Metrics;
 use crate::models::{ArgusError, Result};
-use ethers::providers::{Http, Middleware, Provider};
-use ethers::types::{Address, Block, Bytes, H256, U256};
-use std::sync::Arc;
+use crate::retry::RetryPolicy;
+use endpoint::{is_endpoint_failure, Endpoint};
+use ethers::providers::{Middleware, Provider, ProviderError, PubsubClient};
+use ethers::types::{
+    spoof, transaction::eip2718::TypedTransaction, Address, Block, BlockId, BlockNumber, Bytes,
+    Eip1559TransactionRequest, Filter, Log, TransactionRequest, H256, U256,
+};
+use futures::stream::{self, BoxStream, StreamExt};
+use std::collections::HashMap;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::warn;
+
+const GAS_PRICE_ORACLE_ADDRESS: &str = "0x420000000000000000000000000000000000000F";
+
+// Representative envelope fields for pricing swap calldata before it is signed
+const ENVELOPE_NONCE: u64 = 1_000;
+const ENVELOPE_GAS_LIMIT: u64 = 300_000;
+const ENVELOPE_MAX_FEE_PER_GAS: u64 = 100_000_000;
+const ENVELOPE_MAX_PRIORITY_FEE_PER_GAS: u64 = 1_000_000;
+
+// Endpoints further behind the highest reported block than this are considered lagging
+const MAX_HEALTHY_BLOCK_LAG: u64 = 3;
 
+// Reorg events buffered per subscriber before the oldest are dropped
+const REORG_CHANNEL_CAPACITY: usize = 16;
+
+// Blocks of fee history a percentile priority fee is taken over
+const PRIORITY_FEE_HISTORY_BLOCKS: u64 = 20;
+
+/// Client for one chain over a prioritised set of endpoints. Requests go to the preferred
+/// healthy endpoint and fail over to the next one on transport errors or timeouts; when
+/// every endpoint failed, the whole pass is retried according to the retry policy.
 pub struct RpcClient {
-    provider: Arc<Provider<Http>>,
+    endpoints: Vec<Endpoint>,
     chain_id: u64,
+    quorum: usize,
+    typical_swap_gas: u64,
+    l1_fee_model: L1FeeModel,
+    retry: RetryPolicy,
+    /// Client label for retry logs and metrics
+    name: String,
+    l1_fee_params: Mutex<Option<L1FeeParams>>,
+    /// Last block whose locally computed L1 data fee was checked against the oracle
+    l1_fee_verified_block: AtomicU64,
+    /// Recent canonical blocks; held across the parent walk so blocks are observed in turn
+    block_history: tokio::sync::Mutex<BlockHistory>,
+    reorgs: broadcast::Sender<ReorgEvent>,
 }
 
 impl RpcClient {
-    pub async fn new(rpc_url: &str, chain_id: u64) -> Result<Self> {
-        let provider = Provider::<Http>::try_from(rpc_url)
-            .map_err(|e| ArgusError::RpcError(format!("Failed to create provider: {e}")))?;
+    /// Connects to every configured endpoint of `chain`; endpoints that cannot be reached
+    /// are skipped, while one serving the wrong chain is a configuration error.
+    /// `ws://` and `wss://` endpoints also enable block subscriptions.
+    pub async fn new(chain: &ChainConfig, retry: RetryPolicy) -> Result<Self> {
+        let connections =
+            futures::future::join_all(chain.rpc_endpoints.iter().map(|endpoint| {
+                Endpoint::connect(&endpoint.url, endpoint.priority, chain.chain_id)
+            }))
+            .await;
 
-        let chain = provider
-            .get_chainid()
-            .await
-            .map_err(|e| ArgusError::RpcError(format!("Failed to get chain ID: {e}")))?;
+        let mut endpoints = Vec::with_capacity(connections.len());
+        for connection in connections {
+            match connection {
+                Ok(endpoint) => endpoints.push(endpoint),
+                Err(e @ ArgusError::ConfigError(_)) => return Err(e),
+                Err(e) => warn!("Skipping RPC endpoint for chain {}: {}", chain.chain_id, e),
+            }
+        }
 
-        if chain.as_u64() != chain_id {
+        if endpoints.is_empty() {
             return Err(ArgusError::RpcError(format!(
-                "Chain ID mismatch: expected {}, got {}",
-                chain_id,
-                chain.as_u64()
+                "No reachable RPC endpoint for chain {}",
+                chain.chain_id
             )));
         }
+        if endpoints.len() < chain.rpc_quorum {
+            warn!(
+                "Only {} RPC endpoints reachable for chain {}, quorum reads need {}",
+                endpoints.len(),
+                chain.chain_id,
+                chain.rpc_quorum
+            );
+        }
+        endpoints.sort_by_key(|endpoint| endpoint.priority);
 
         Ok(Self {
-            provider: Arc::new(provider),
-            chain_id,
+            endpoints,
+            chain_id: chain.chain_id,
+            quorum: chain.rpc_quorum,
+            typical_swap_gas: chain.typical_swap_gas,
+            l1_fee_model: chain.l1_fee_model,
+            retry,
+            name: format!("rpc_{}", chain.chain_id),
+            l1_fee_params: Mutex::new(None),
+            l1_fee_verified_block: AtomicU64::new(0),
+            block_history: tokio::sync::Mutex::new(BlockHistory::new()),
+            reorgs: broadcast::channel(REORG_CHANNEL_CAPACITY).0,
         })
     }
 
+    /// Provider of the preferred healthy endpoint.
     #[must_use]
-    pub fn provider(&self) -> Arc<Provider<Http>> {
-        self.provider.clone()
+    pub fn provider(&self) -> Arc<Provider<Transport>> {
+        self.preferred_endpoints()
+            .next()
+            .map(|endpoint| endpoint.provider.clone())
+            .expect("RpcClient has at least one endpoint")
+    }
+
+    /// Whether any endpoint can push `newHeads` notifications.
+    #[must_use]
+    pub fn supports_subscriptions(&self) -> bool {
+        self.endpoints
+            .iter()
+            .any(|endpoint| matches!(endpoint.provider.as_ref().as_ref(), Transport::Ws(_)))
+    }
+
+    /// Stream of new block headers: pushed through an `eth_subscribe("newHeads")`
+    /// subscription on the preferred WebSocket endpoint, or polled every `poll_interval`
+    /// with failover when there is none.
+    pub async fn new_blocks(
+        self: &Arc<Self>,
+        poll_interval: Duration,
+    ) -> Result<BoxStream<'static, Result<Block<H256>>>> {
+        let ws_endpoint = self.preferred_endpoints().find_map(|endpoint| {
+            match endpoint.provider.as_ref().as_ref() {
+                Transport::Ws(ws) => Some((endpoint, ws)),
+                Transport::Http(_) => None,
+            }
+        });
+
+        if let Some((endpoint, ws)) = ws_endpoint {
+            let subscription_id: U256 = endpoint
+                .provider
+                .request("eth_subscribe", ["newHeads"])
+                .await
+                .map_err(|e| {
+                    ArgusError::RpcError(format!(
+                        "Failed to subscribe to newHeads on {}: {e}",
+                        endpoint.label
+                    ))
+                })?;
+            let notifications = ws.subscribe(subscription_id).map_err(|e| {
+                ArgusError::RpcError(format!("Failed to listen to newHeads subscription: {e}"))
+            })?;
+
+            let client = self.clone();
+            return Ok(notifications
+                .map(|header| Ok(serde_json::from_str::<Block<H256>>(header.get())?))
+                .then(move |block: Result<Block<H256>>| {
+                    let client = client.clone();
+                    async move {
+                        if let Ok(block) = &block {
+                            client.observe_block(block).await;
+                        }
+                        block
+                    }
+                })
+                .boxed());
+        }
+
+        let mut ticker = tokio::time::interval(poll_interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        Ok(stream::unfold(
+            (self.clone(), ticker, None::<H256>),
+            |(client, mut ticker, mut last_hash)| async move {
+                loop {
+                    ticker.tick().await;
+
+                    let block = match client.get_latest_block().await {
+                        Ok(block) if block.hash.is_some() && block.hash == last_hash => continue,
+                        Ok(block) => {
+                            last_hash = block.hash;
+                            Ok(block)
+                        }
+                        Err(e) => Err(e),
+                    };
+                    return Some((block, (client, ticker, last_hash)));
+                }
+            },
+        )
+        .boxed())
+    }
+
+    /// Periodically probes every endpoint with `eth_blockNumber`, marking endpoints that
+    /// fail, time out or lag behind the others as unhealthy and recovered ones as healthy.
+    pub fn spawn_health_checks(self: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+            loop {
+                ticker.tick().await;
+                client.check_endpoints().await;
+            }
+        })
+    }
+
+    async fn check_endpoints(&self) {
+        let heads = futures::future::join_all(self.endpoints.iter().map(|endpoint| async move {
+            let started = Instant::now();
+            let head =
+                tokio::time::timeout(self.retry.timeout, endpoint.provider.get_block_number())
+                    .await;
+            Metrics::global().observe_rpc_latency(self.chain_id, "eth_blockNumber", started);
+            head
+        }))
+        .await;
+
+        let best = heads
+            .iter()
+            .filter_map(|head| match head {
+                Ok(Ok(number)) => Some(number.as_u64()),
+                _ => None,
+            })
+            .max()
+            .unwrap_or_default();
+
+        for (endpoint, head) in self.endpoints.iter().zip(heads) {
+            match head {
+                Ok(Ok(number)) if best - number.as_u64() > MAX_HEALTHY_BLOCK_LAG => endpoint
+                    .mark_unhealthy(&format!("lagging {} blocks behind", best - number.as_u64())),
+                Ok(Ok(_)) => endpoint.mark_healthy(),
+                Ok(Err(e)) => endpoint.mark_unhealthy(&format!("health check failed: {e}")),
+                Err(_) => endpoint.mark_unhealthy("health check timed out"),
+            }
+        }
+    }
+
+    /// Healthy endpoints in priority order, followed by unhealthy ones as a last resort.
+    fn preferred_endpoints(&self) -> impl Iterator<Item = &Endpoint> {
+        self.endpoints
+            .iter()
+            .filter(|endpoint| endpoint.is_healthy())
+            .chain(
+                self.endpoints
+                    .iter()
+                    .filter(|endpoint| !endpoint.is_healthy()),
+            )
+    }
+
+    /// Runs `request` with failover, retrying the failover pass with backoff when every
+    /// endpoint failed.
+    async fn request<T, F, Fut>(
+        &self,
+        method: &str,
+        request: F,
+    ) -> std::result::Result<T, ProviderError>
+    where
+        F: Fn(Arc<Provider<Transport>>) -> Fut,
+        Fut: Future<Output = std::result::Result<T, ProviderError>>,
+    {
+        self.retry
+            .retry(&self.name, method, || self.request_once(method, &request))
+            .await
+    }
+
+    /// Runs `request` against the preferred endpoint, failing over to the next one when an
+    /// endpoint errors at the transport level or exceeds the request timeout. JSON-RPC error
+    /// responses (such as reverts) are returned as-is.
+    async fn request_once<T, F, Fut>(
+        &self,
+        method: &str,
+        request: &F,
+    ) -> std::result::Result<T, ProviderError>
+    where
+        F: Fn(Arc<Provider<Transport>>) -> Fut,
+        Fut: Future<Output = std::result::Result<T, ProviderError>>,
+    {
+        let mut last_error = None;
+
+        for endpoint in self.preferred_endpoints() {
+            let started = Instant::now();
+            let result =
+                tokio::time::timeout(self.retry.timeout, request(endpoint.provider.clone())).await;
+            Metrics::global().observe_rpc_latency(self.chain_id, method, started);
+
+            let error = match result {
+                Ok(Ok(value)) => {
+                    endpoint.mark_healthy();
+                    return Ok(value);
+                }
+                Ok(Err(e)) if !is_endpoint_failure(&e) => return Err(e),
+                Ok(Err(e)) => e,
+                Err(_) => ProviderError::CustomError(format!(
+                    "{method} timed out after {}ms",
+                    self.retry.timeout.as_millis()
+                )),
+            };
+            endpoint.mark_unhealthy(&format!("{method}: {error}"));
+            last_error = Some(error);
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            ProviderError::CustomError("No RPC endpoints available".to_string())
+        }))
     }
 
     #[must_use]
@@ -46,10 +328,164 @@ impl RpcClient {
         self.chain_id
     }
 
+    /// Executes an `eth_call` against `to`, recording its latency under `method`.
+    pub async fn call(&self, to: Address, data: Vec<u8>, method: &str) -> Result<Bytes> {
+        self.call_at(to, data, None, method).await
+    }
+
+    /// Executes an `eth_call` against `to` at `block`, or at the latest block when `None`.
+    async fn call_at(
+        &self,
+        to: Address,
+        data: Vec<u8>,
+        block: Option<BlockId>,
+        method: &str,
+    ) -> Result<Bytes> {
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(to)
+            .data(Bytes::from(data))
+            .into();
+
+        self.request(method, |provider| {
+            let tx = tx.clone();
+            async move { provider.call(&tx, block).await }
+        })
+        .await
+        .map_err(|e| ArgusError::ContractError(format!("Failed to call {method}: {e}")))
+    }
+
+    /// Executes an `eth_call` that must return identical results from at least `quorum`
+    /// healthy endpoints at the same block, to catch lagging or misbehaving nodes.
+    /// Endpoints that disagree with the majority are marked unhealthy. With a quorum of one
+    /// this is a plain `call`.
+    pub async fn quorum_call(&self, to: Address, data: Vec<u8>, method: &str) -> Result<Bytes> {
+        if self.quorum <= 1 {
+            return self.call(to, data, method).await;
+        }
+
+        let block_number = self.get_block_number().await?;
+        self.quorum_call_at(to, data, block_number, method).await
+    }
+
+    /// Batches `calls` into one Multicall3 `aggregate3` call so every result is read at the
+    /// same block: `block_number`, or the latest block when `None`. The batch is subject to
+    /// the same endpoint quorum as `quorum_call`.
+    pub async fn multicall(
+        &self,
+        calls: &[Call3],
+        block_number: Option<u64>,
+        method: &str,
+    ) -> Result<MulticallResult> {
+        let block_number = match block_number {
+            Some(block_number) => block_number,
+            None => self.get_block_number().await?,
+        };
+
+        let multicall = Address::from_str(MULTICALL3_ADDRESS)
+            .map_err(|e| ArgusError::ContractError(format!("Invalid Multicall3 address: {e}")))?;
+        let data = multicall::encode_aggregate3(calls);
+
+        let response = if self.quorum <= 1 {
+            let block = BlockId::Number(BlockNumber::Number(block_number.into()));
+            self.call_at(multicall, data, Some(block), method).await?
+        } else {
+            self.quorum_call_at(multicall, data, block_number, method)
+                .await?
+        };
+
+        Ok(MulticallResult {
+            block_number,
+            results: multicall::decode_aggregate3(&response, calls.len())?,
+        })
+    }
+
+    async fn quorum_call_at(
+        &self,
+        to: Address,
+        data: Vec<u8>,
+        block_number: u64,
+        method: &str,
+    ) -> Result<Bytes> {
+        let block = BlockId::Number(BlockNumber::Number(block_number.into()));
+        let tx: TypedTransaction = TransactionRequest::new()
+            .to(to)
+            .data(Bytes::from(data))
+            .into();
+
+        let healthy: Vec<&Endpoint> = self.endpoints.iter().filter(|e| e.is_healthy()).collect();
+        let responses = futures::future::join_all(healthy.iter().map(|endpoint| {
+            let tx = &tx;
+            async move {
+                let started = Instant::now();
+                let response = tokio::time::timeout(
+                    self.retry.timeout,
+                    endpoint.provider.call(tx, Some(block)),
+                )
+                .await;
+                Metrics::global().observe_rpc_latency(self.chain_id, method, started);
+                response
+            }
+        }))
+        .await;
+
+        let mut votes: HashMap<&Bytes, usize> = HashMap::new();
+        for response in &responses {
+            if let Ok(Ok(result)) = response {
+                *votes.entry(result).or_default() += 1;
+            }
+        }
+        let (majority, agreeing) = votes
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map_or((None, 0), |(result, count)| (Some(result.clone()), count));
+
+        for (endpoint, response) in healthy.iter().zip(&responses) {
+            match response {
+                Ok(Ok(result)) if Some(result) != majority.as_ref() => endpoint.mark_unhealthy(
+                    &format!("{method} result disagrees with {agreeing} other endpoints"),
+                ),
+                Ok(Err(e)) if is_endpoint_failure(e) => {
+                    endpoint.mark_unhealthy(&format!("{method}: {e}"));
+                }
+                Err(_) => endpoint.mark_unhealthy(&format!("{method} timed out")),
+                _ => {}
+            }
+        }
+
+        match majority {
+            Some(result) if agreeing >= self.quorum => Ok(result),
+            _ => Err(ArgusError::RpcError(format!(
+                "No quorum for {method} at block {block:?}: {agreeing} of {} endpoints agree, {} required",
+                healthy.len(),
+                self.quorum
+            ))),
+        }
+    }
+
+    /// Runs `eth_estimateGas` for `tx` at `block_number` with `overrides` applied.
+    pub async fn estimate_gas_with_overrides(
+        &self,
+        tx: &TransactionRequest,
+        overrides: &spoof::State,
+        block_number: u64,
+    ) -> Result<u64> {
+        let block = BlockNumber::Number(block_number.into());
+        let gas = self
+            .request("eth_estimateGas", |provider| async move {
+                provider
+                    .request::<_, U256>("eth_estimateGas", (tx, block, overrides))
+                    .await
+            })
+            .await
+            .map_err(|e| ArgusError::RpcError(format!("Failed to estimate gas: {e}")))?;
+        Ok(gas.as_u64())
+    }
+
     pub async fn get_gas_price(&self) -> Result<u64> {
         let gas_price = self
-            .provider
-            .get_gas_price()
+            .request("eth_gasPrice", |provider| async move {
+                provider.get_gas_price().await
+            })
             .await
             .map_err(|e| ArgusError::RpcError(format!("Failed to get gas price: {e}")))?;
 
@@ -67,83 +503,299 @@ impl RpcClient {
         Ok(gas_price * gas_units)
     }
 
-    pub fn get_typical_swap_gas(&self) -> Result<u64> {
-        // Return typical gas units for DEX swaps on each chain
-        // These are well-documented values from mainnet observations
-        // We use real-time gas prices with these typical units
-        // Also Documented why this approach was choosen in DESIGN_CHOICES.md
-
-        if self.chain_id == 1 {
-            // Ethereum mainnet - Uniswap V4 swap typically uses 150,000 gas
-            // Source: Uniswap V4 documentation and mainnet observations
-            Ok(150_000)
-        } else if self.chain_id == 8453 {
-            // Base - Aerodrome swap typically uses 80,000 gas
-            // Source: Aerodrome documentation and Base mainnet observations
-            Ok(80_000)
-        } else {
-            Err(ArgusError::RpcError(format!(
-                "Unsupported chain ID: {}",
-                self.chain_id
-            )))
-        }
+    /// Why typical units are used is explained in `DESIGN_CHOICES.md`.
+    #[must_use]
+    pub fn get_typical_swap_gas(&self) -> u64 {
+        self.typical_swap_gas
     }
 
+    /// Latest block, checked against the tracked canonical history for reorgs.
     pub async fn get_latest_block(&self) -> Result<Block<H256>> {
         let block = self
-            .provider
-            .get_block(ethers::types::BlockNumber::Latest)
+            .request("eth_getBlockByNumber", |provider| async move {
+                provider.get_block(BlockNumber::Latest).await
+            })
             .await
             .map_err(|e| ArgusError::RpcError(format!("Failed to get latest block: {e}")))?
             .ok_or_else(|| ArgusError::RpcError("Latest block not found".to_string()))?;
+
+        self.observe_block(&block).await;
         Ok(block)
     }
 
+    async fn get_block_by_hash(&self, hash: H256) -> Result<Block<H256>> {
+        self.request("eth_getBlockByHash", |provider| async move {
+            provider.get_block(hash).await
+        })
+        .await
+        .map_err(|e| ArgusError::RpcError(format!("Failed to get block {hash:?}: {e}")))?
+        .ok_or_else(|| ArgusError::RpcError(format!("Block {hash:?} not found")))
+    }
+
+    /// Receiver of the reorgs detected on this chain from now on.
+    #[must_use]
+    pub fn subscribe_reorgs(&self) -> broadcast::Receiver<ReorgEvent> {
+        self.reorgs.subscribe()
+    }
+
+    /// Whether the block `number` with `hash` is still canonical as far as the tracked
+    /// history knows; blocks outside the tracked range are assumed canonical.
+    pub async fn is_canonical(&self, number: u64, hash: H256) -> bool {
+        self.block_history.lock().await.is_canonical(number, hash)
+    }
+
+    /// Adds `block` to the canonical history. When its parent does not match the tracked
+    /// block below it, parents are fetched until the chains meet; the tracked blocks that
+    /// were replaced are reported as a reorg.
+    async fn observe_block(&self, block: &Block<H256>) {
+        let (Some(number), Some(hash)) = (block.number, block.hash) else {
+            return;
+        };
+        let number = number.as_u64();
+
+        let mut history = self.block_history.lock().await;
+        if history.get(number) == Some(hash) || history.floor().is_some_and(|floor| number < floor)
+        {
+            // Already tracked, or a stale head from a lagging endpoint
+            return;
+        }
+        if history
+            .tip()
+            .is_some_and(|tip| number > tip + TRACKED_BLOCKS as u64)
+        {
+            // Too far ahead to connect to the tracked blocks, which are all stale
+            history.clear();
+        }
+
+        let mut chain = vec![(number, hash)];
+        let mut parent_hash = block.parent_hash;
+        let mut parent_number = number.checked_sub(1);
+        while let Some(current) = parent_number {
+            let tracked = history.get(current);
+            if tracked == Some(parent_hash)
+                || history.floor().is_none_or(|floor| current < floor)
+                || chain.len() > TRACKED_BLOCKS
+            {
+                break;
+            }
+
+            match self.get_block_by_hash(parent_hash).await {
+                Ok(parent) => {
+                    chain.push((current, parent_hash));
+                    parent_hash = parent.parent_hash;
+                    parent_number = current.checked_sub(1);
+                }
+                Err(e) => {
+                    warn!(
+                        "Failed to walk back from block {} on chain {}: {}",
+                        number, self.chain_id, e
+                    );
+                    break;
+                }
+            }
+        }
+
+        let orphaned = history.extend(&chain);
+        if orphaned.is_empty() {
+            return;
+        }
+
+        warn!(
+            "Reorg of depth {} on chain {}: new head {} ({:?})",
+            orphaned.len(),
+            self.chain_id,
+            number,
+            hash
+        );
+        Metrics::global().record_reorg(self.chain_id);
+        // Sending fails only when nobody is subscribed
+        let _ = self.reorgs.send(ReorgEvent {
+            chain_id: self.chain_id,
+            depth: orphaned.len(),
+            orphaned,
+            new_head_number: number,
+            new_head_hash: hash,
+        });
+    }
+
+    /// Logs matching `filter`, in block and log index order.
+    pub async fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>> {
+        self.request("eth_getLogs", |provider| async move {
+            provider.get_logs(filter).await
+        })
+        .await
+        .map_err(|e| ArgusError::RpcError(format!("Failed to get logs: {e}")))
+    }
+
+    pub async fn get_block_number(&self) -> Result<u64> {
+        let block_number = self
+            .request("eth_blockNumber", |provider| async move {
+                provider.get_block_number().await
+            })
+            .await
+            .map_err(|e| ArgusError::RpcError(format!("Failed to get block number: {e}")))?;
+        Ok(block_number.as_u64())
+    }
+
     pub async fn get_max_priority_fee_per_gas(&self) -> Result<u64> {
         // Try to get suggested priority fee - NO FALLBACK
         let priority_fee = self
-            .provider
-            .request::<_, U256>("eth_maxPriorityFeePerGas", ())
+            .request("eth_maxPriorityFeePerGas", |provider| async move {
+                provider
+                    .request::<_, U256>("eth_maxPriorityFeePerGas", ())
+                    .await
+            })
             .await
             .map_err(|e| ArgusError::RpcError(format!("Cannot get priority fee from RPC: {e}")))?;
 
         Ok(priority_fee.as_u64())
     }
 
+    /// Median, over the `PRIORITY_FEE_HISTORY_BLOCKS` blocks up to `block_number`, of the
+    /// priority fee paid at `percentile` of each block's gas.
+    pub async fn get_priority_fee_percentile(
+        &self,
+        block_number: u64,
+        percentile: f64,
+    ) -> Result<u64> {
+        let history = self
+            .request("eth_feeHistory", |provider| async move {
+                provider
+                    .fee_history(
+                        PRIORITY_FEE_HISTORY_BLOCKS,
+                        BlockNumber::Number(block_number.into()),
+                        &[percentile],
+                    )
+                    .await
+            })
+            .await
+            .map_err(|e| ArgusError::RpcError(format!("Cannot get fee history from RPC: {e}")))?;
+
+        let mut rewards: Vec<U256> = history
+            .reward
+            .iter()
+            .filter_map(|block| block.first().copied())
+            .collect();
+        if rewards.is_empty() {
+            return Err(ArgusError::RpcError(
+                "Fee history returned no priority fees".to_string(),
+            ));
+        }
+
+        rewards.sort_unstable();
+        Ok(rewards[rewards.len() / 2].as_u64())
+    }
+
+    /// `GasPriceOracle` fee parameters at `block_number`, read in one batch and cached for
+    /// the block.
+    pub async fn get_l1_fee_params(&self, block_number: u64) -> Result<L1FeeParams> {
+        if let Some(params) = *self.l1_fee_params.lock().unwrap() {
+            if params.block_number == block_number {
+                return Ok(params);
+            }
+        }
+
+        let oracle = Self::gas_price_oracle_address()?;
+        let calls: Vec<Call3> = [
+            "l1BaseFee()",
+            "blobBaseFee()",
+            "baseFeeScalar()",
+            "blobBaseFeeScalar()",
+            "isFjord()",
+        ]
+        .iter()
+        .map(|signature| Call3::function(oracle, signature, &[]))
+        .collect();
+
+        let snapshot = self
+            .multicall(&calls, Some(block_number), "gasPriceOracle")
+            .await?;
+        let read = |index: usize, method: &str| -> Result<U256> {
+            Ok(U256::from_big_endian(
+                &snapshot.output(index, method, 32)?[0..32],
+            ))
+        };
+
+        let l1_base_fee = read(0, "l1BaseFee")?;
+        let blob_base_fee = read(1, "blobBaseFee")?;
+        let base_fee_scalar = read(2, "baseFeeScalar")?;
+        let blob_base_fee_scalar = read(3, "blobBaseFeeScalar")?;
+        let is_fjord = read(4, "isFjord")?;
+
+        let params = L1FeeParams {
+            block_number,
+            l1_base_fee,
+            blob_base_fee,
+            base_fee_scalar: base_fee_scalar.as_u32(),
+            blob_base_fee_scalar: blob_base_fee_scalar.as_u32(),
+            is_fjord: !is_fjord.is_zero(),
+        };
+        *self.l1_fee_params.lock().unwrap() = Some(params);
+
+        Ok(params)
+    }
+
+    /// L1 data fee in wei for sending `calldata` to `to`, computed locally from the
+    /// oracle parameters of `block_number`. The first estimate of each block is also
+    /// checked against `GasPriceOracle.getL1Fee`, and a mismatch is logged and counted.
     pub async fn estimate_l1_data_fee(
         &self,
-        _to_address: Address,
+        block_number: u64,
+        to_address: Address,
         calldata: Vec<u8>,
     ) -> Result<u64> {
-        if self.chain_id != 8453 {
+        if self.l1_fee_model == L1FeeModel::None {
             return Ok(0);
         }
 
-        let oracle_address: Address = "0x420000000000000000000000000000000000000F"
-            .parse()
-            .unwrap();
+        let envelope = self.unsigned_envelope(to_address, calldata);
+        let verify = self
+            .l1_fee_verified_block
+            .swap(block_number, Ordering::Relaxed)
+            != block_number;
+        let (params, oracle_fee) = tokio::join!(self.get_l1_fee_params(block_number), async {
+            if verify {
+                Some(self.get_oracle_l1_data_fee(block_number, &envelope).await)
+            } else {
+                None
+            }
+        });
+        let l1_fee_wei = params?.l1_fee(&envelope);
 
-        let mut tx_bytes = Vec::new();
+        match oracle_fee {
+            Some(Ok(oracle_fee_wei)) if oracle_fee_wei != l1_fee_wei => {
+                warn!(
+                    "Local L1 data fee {} wei differs from GasPriceOracle's {} wei at block {}",
+                    l1_fee_wei, oracle_fee_wei, block_number
+                );
+                Metrics::global().record_l1_fee_mismatch(self.chain_id);
+            }
+            Some(Err(e)) => warn!("Cannot check L1 data fee against GasPriceOracle: {}", e),
+            _ => {}
+        }
 
-        tx_bytes.extend_from_slice(&[0x02]);
-        tx_bytes.extend_from_slice(&calldata.len().to_be_bytes()[6..]);
-        tx_bytes.extend_from_slice(&calldata);
+        Ok(l1_fee_wei.as_u64())
+    }
 
+    /// L1 data fee quoted by `GasPriceOracle.getL1Fee` at `block_number` for an unsigned
+    /// transaction envelope.
+    async fn get_oracle_l1_data_fee(&self, block_number: u64, envelope: &[u8]) -> Result<U256> {
         let get_l1_fee_selector = &ethers::utils::keccak256(b"getL1Fee(bytes)")[0..4];
 
-        let encoded_params = ethers::abi::encode(&[ethers::abi::Token::Bytes(tx_bytes)]);
+        let encoded_params = ethers::abi::encode(&[ethers::abi::Token::Bytes(envelope.to_vec())]);
 
         let mut oracle_call_data = Vec::from(get_l1_fee_selector);
         oracle_call_data.extend_from_slice(&encoded_params);
 
-        let tx = ethers::types::TransactionRequest::new()
-            .to(oracle_address)
-            .data(Bytes::from(oracle_call_data));
-
-        let result =
-            self.provider.call(&tx.into(), None).await.map_err(|e| {
-                ArgusError::RpcError(format!("Failed to get L1 fee from oracle: {e}"))
-            })?;
+        let result = self
+            .call_at(
+                Self::gas_price_oracle_address()?,
+                oracle_call_data,
+                Some(BlockId::Number(BlockNumber::Number(block_number.into()))),
+                "getL1Fee",
+            )
+            .await
+            .map_err(|e| ArgusError::RpcError(format!("Failed to get L1 fee from oracle: {e}")))?;
 
         if result.len() < 32 {
             return Err(ArgusError::RpcError(
@@ -151,8 +803,27 @@ impl RpcClient {
             ));
         }
 
-        let l1_fee_wei = U256::from_big_endian(&result[0..32]);
+        Ok(U256::from_big_endian(&result[0..32]))
+    }
 
-        Ok(l1_fee_wei.as_u64())
+    /// Unsigned EIP-1559 transaction carrying `calldata`, as the oracle expects it.
+    fn unsigned_envelope(&self, to_address: Address, calldata: Vec<u8>) -> Vec<u8> {
+        let tx: TypedTransaction = Eip1559TransactionRequest::new()
+            .chain_id(self.chain_id)
+            .nonce(ENVELOPE_NONCE)
+            .gas(ENVELOPE_GAS_LIMIT)
+            .max_fee_per_gas(ENVELOPE_MAX_FEE_PER_GAS)
+            .max_priority_fee_per_gas(ENVELOPE_MAX_PRIORITY_FEE_PER_GAS)
+            .to(to_address)
+            .data(calldata)
+            .into();
+
+        tx.rlp().to_vec()
+    }
+
+    fn gas_price_oracle_address() -> Result<Address> {
+        GAS_PRICE_ORACLE_ADDRESS
+            .parse()
+            .map_err(|e| ArgusError::RpcError(format!("Invalid GasPriceOracle address: {e}")))
     }
 }
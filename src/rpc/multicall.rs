@@ -0,0 +1,133 @@
+/*
+ * Multicall3 `aggregate3` encoding for batching calls at a single block
+ */
+
+use crate::models::{ArgusError, Result};
+use ethers::{
+    abi::{decode, encode, ParamType, Token},
+    types::{Address, Bytes},
+    utils::keccak256,
+};
+
+/// Multicall3, deployed at the same address on every supported chain.
+pub const MULTICALL3_ADDRESS: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
+/// One call of an `aggregate3` batch.
+#[derive(Debug, Clone)]
+pub struct Call3 {
+    pub target: Address,
+    /// When false, a revert of this call reverts the whole batch
+    pub allow_failure: bool,
+    pub call_data: Vec<u8>,
+}
+
+impl Call3 {
+    /// Call whose revert fails the whole batch.
+    #[must_use]
+    pub fn new(target: Address, call_data: Vec<u8>) -> Self {
+        Self {
+            target,
+            allow_failure: false,
+            call_data,
+        }
+    }
+
+    /// Call of `signature` on `target` with ABI-encoded `args`.
+    #[must_use]
+    pub fn function(target: Address, signature: &str, args: &[Token]) -> Self {
+        let mut call_data = keccak256(signature.as_bytes())[0..4].to_vec();
+        call_data.extend_from_slice(&encode(args));
+        Self::new(target, call_data)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Call3Result {
+    pub success: bool,
+    pub return_data: Bytes,
+}
+
+/// Results of an `aggregate3` batch, in call order, and the block they were read at.
+#[derive(Debug, Clone)]
+pub struct MulticallResult {
+    pub block_number: u64,
+    pub results: Vec<Call3Result>,
+}
+
+impl MulticallResult {
+    /// Return data of call `index`, checked to hold at least `min_len` bytes.
+    pub fn output(&self, index: usize, method: &str, min_len: usize) -> Result<&Bytes> {
+        let result = self.results.get(index).ok_or_else(|| {
+            ArgusError::ContractError(format!("Missing {method} result in multicall"))
+        })?;
+
+        if !result.success {
+            return Err(ArgusError::ContractError(format!(
+                "{method} reverted in multicall at block {}",
+                self.block_number
+            )));
+        }
+        if result.return_data.len() < min_len {
+            return Err(ArgusError::ContractError(format!(
+                "Invalid {method} response"
+            )));
+        }
+
+        Ok(&result.return_data)
+    }
+}
+
+/// Calldata for `aggregate3((address,bool,bytes)[])`.
+pub(crate) fn encode_aggregate3(calls: &[Call3]) -> Vec<u8> {
+    let calls = calls
+        .iter()
+        .map(|call| {
+            Token::Tuple(vec![
+                Token::Address(call.target),
+                Token::Bool(call.allow_failure),
+                Token::Bytes(call.call_data.clone()),
+            ])
+        })
+        .collect();
+
+    let mut call_data = keccak256(b"aggregate3((address,bool,bytes)[])")[0..4].to_vec();
+    call_data.extend_from_slice(&encode(&[Token::Array(calls)]));
+    call_data
+}
+
+/// Decodes the `(bool,bytes)[]` returned by `aggregate3`, expecting `expected` results.
+pub(crate) fn decode_aggregate3(data: &[u8], expected: usize) -> Result<Vec<Call3Result>> {
+    let result_type = ParamType::Array(Box::new(ParamType::Tuple(vec![
+        ParamType::Bool,
+        ParamType::Bytes,
+    ])));
+
+    let decoded = decode(&[result_type], data)
+        .map_err(|e| ArgusError::ContractError(format!("Invalid aggregate3 response: {e}")))?;
+
+    let results: Vec<Call3Result> = match decoded.into_iter().next() {
+        Some(Token::Array(results)) => results
+            .into_iter()
+            .filter_map(|result| match result {
+                Token::Tuple(fields) => match fields.as_slice() {
+                    [Token::Bool(success), Token::Bytes(return_data)] => Some(Call3Result {
+                        success: *success,
+                        return_data: Bytes::from(return_data.clone()),
+                    }),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    if results.len() != expected {
+        return Err(ArgusError::ContractError(format!(
+            "aggregate3 returned {} results for {expected} calls",
+            results.len()
+        )));
+    }
+
+    Ok(results)
+}
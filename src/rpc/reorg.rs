@@ -0,0 +1,138 @@
+/*
+ * Recent canonical block hashes of one chain, for detecting reorgs
+ */
+
+use ethers::types::H256;
+use std::collections::BTreeMap;
+
+/// Blocks kept per chain; also the deepest reorg that can be reported exactly.
+pub const TRACKED_BLOCKS: usize = 64;
+
+/// A reorg observed on one chain.
+#[derive(Debug, Clone)]
+pub struct ReorgEvent {
+    pub chain_id: u64,
+    /// Number of previously canonical blocks that were replaced
+    pub depth: usize,
+    /// Number and hash of every replaced block, oldest first
+    pub orphaned: Vec<(u64, H256)>,
+    pub new_head_number: u64,
+    pub new_head_hash: H256,
+}
+
+/// Hashes of the most recent canonical blocks, by number.
+#[derive(Debug, Default)]
+pub struct BlockHistory {
+    blocks: BTreeMap<u64, H256>,
+}
+
+impl BlockHistory {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn get(&self, number: u64) -> Option<H256> {
+        self.blocks.get(&number).copied()
+    }
+
+    /// Highest tracked block number.
+    #[must_use]
+    pub fn tip(&self) -> Option<u64> {
+        self.blocks.keys().next_back().copied()
+    }
+
+    /// Lowest tracked block number.
+    #[must_use]
+    pub fn floor(&self) -> Option<u64> {
+        self.blocks.keys().next().copied()
+    }
+
+    /// Whether `hash` is, as far as the tracked range knows, canonical at `number`.
+    #[must_use]
+    pub fn is_canonical(&self, number: u64, hash: H256) -> bool {
+        self.get(number).is_none_or(|known| known == hash)
+    }
+
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+
+    /// Makes `chain` (consecutive blocks, any order) the canonical tail of the history and
+    /// returns the tracked blocks it replaced, oldest first. A chain whose head is below
+    /// the tracked range is stale and ignored.
+    pub fn extend(&mut self, chain: &[(u64, H256)]) -> Vec<(u64, H256)> {
+        let (Some(lowest), Some(head)) = (
+            chain.iter().map(|(number, _)| *number).min(),
+            chain.iter().map(|(number, _)| *number).max(),
+        ) else {
+            return Vec::new();
+        };
+        if self.floor().is_some_and(|floor| head < floor) {
+            return Vec::new();
+        }
+
+        let replaced = self.blocks.split_off(&lowest);
+        self.blocks.extend(chain.iter().copied());
+
+        let orphaned = replaced
+            .into_iter()
+            .filter(|(number, hash)| self.get(*number) != Some(*hash))
+            .collect();
+
+        while self.blocks.len() > TRACKED_BLOCKS {
+            self.blocks.pop_first();
+        }
+
+        orphaned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(n: u64) -> H256 {
+        H256::from_low_u64_be(n)
+    }
+
+    fn history(range: std::ops::RangeInclusive<u64>) -> BlockHistory {
+        let mut history = BlockHistory::new();
+        let chain: Vec<_> = range.map(|n| (n, hash(n))).collect();
+        assert!(history.extend(&chain).is_empty());
+        history
+    }
+
+    #[test]
+    fn extend_appends_without_orphans() {
+        let mut history = history(10..=12);
+
+        assert!(history.extend(&[(13, hash(13))]).is_empty());
+        assert_eq!(history.tip(), Some(13));
+        assert_eq!(history.floor(), Some(10));
+        assert_eq!(history.get(13), Some(hash(13)));
+    }
+
+    #[test]
+    fn extend_reports_depth_two_replacement() {
+        let mut history = history(10..=12);
+
+        let orphaned = history.extend(&[(13, hash(113)), (11, hash(111)), (12, hash(112))]);
+
+        assert_eq!(orphaned, vec![(11, hash(11)), (12, hash(12))]);
+        assert_eq!(history.get(10), Some(hash(10)));
+        assert_eq!(history.get(11), Some(hash(111)));
+        assert_eq!(history.tip(), Some(13));
+    }
+
+    #[test]
+    fn extend_ignores_head_below_floor() {
+        let mut history = history(10..=12);
+
+        assert!(history.extend(&[(8, hash(108)), (9, hash(109))]).is_empty());
+        assert_eq!(history.floor(), Some(10));
+        assert_eq!(history.get(8), None);
+        assert_eq!(history.get(12), Some(hash(12)));
+    }
+}
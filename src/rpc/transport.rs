@@ -0,0 +1,81 @@
+/*
+ * JSON-RPC transport selected from the endpoint URL scheme (HTTP or WebSocket)
+ */
+
+use crate::models::{ArgusError, Result};
+use async_trait::async_trait;
+use ethers::providers::{
+    Http, HttpClientError, JsonRpcClient, JsonRpcError, ProviderError, RpcError, Ws, WsClientError,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+#[derive(Debug, Clone)]
+pub enum Transport {
+    Http(Http),
+    /// Reconnecting WebSocket client; active subscriptions are re-established on reconnect
+    Ws(Ws),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TransportError {
+    #[error(transparent)]
+    Http(#[from] HttpClientError),
+
+    #[error(transparent)]
+    Ws(#[from] WsClientError),
+}
+
+impl Transport {
+    /// Connects to `url`, using a WebSocket for `ws://` and `wss://` URLs and HTTP otherwise.
+    pub async fn connect(url: &str) -> Result<Self> {
+        if url.starts_with("ws://") || url.starts_with("wss://") {
+            let ws = Ws::connect(url)
+                .await
+                .map_err(|e| ArgusError::RpcError(format!("Failed to connect WebSocket: {e}")))?;
+            Ok(Self::Ws(ws))
+        } else {
+            let http = url
+                .parse::<Http>()
+                .map_err(|e| ArgusError::RpcError(format!("Failed to create provider: {e}")))?;
+            Ok(Self::Http(http))
+        }
+    }
+}
+
+#[async_trait]
+impl JsonRpcClient for Transport {
+    type Error = TransportError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> std::result::Result<R, Self::Error>
+    where
+        T: std::fmt::Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        match self {
+            Self::Http(client) => Ok(client.request(method, params).await?),
+            Self::Ws(client) => Ok(client.request(method, params).await?),
+        }
+    }
+}
+
+impl RpcError for TransportError {
+    fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            Self::Http(e) => e.as_error_response(),
+            Self::Ws(e) => e.as_error_response(),
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            Self::Http(e) => e.as_serde_error(),
+            Self::Ws(e) => e.as_serde_error(),
+        }
+    }
+}
+
+impl From<TransportError> for ProviderError {
+    fn from(src: TransportError) -> Self {
+        ProviderError::JsonRpcClientError(Box::new(src))
+    }
+}
@@ -3,239 +3,1103 @@
  */
 
 use crate::{
-    analytics::ArbitrageAnalyzer,
+    analytics::{guards::OpportunityGuards, ArbitrageAnalyzer, RoundTripQuote, RouteQuote},
     cex::{create_cex_client, CexClient},
-    config::Config,
-    dex::{DexClient, SwapQuote},
-    models::{ArbitrageOpportunity, Result},
+    config::{
+        AerodromePoolType, ChainConfig, Config, GasEstimationMode, MonitorConfig, MonitorTrigger,
+    },
+    dex::{
+        pool_cache::spawn_pool_sync,
+        uniswap_v4::{PoolKey, UniswapV4Client},
+        DexClient, SwapCalldata, SwapCalldataParams,
+    },
+    history::OpportunityHistory,
+    metrics::Metrics,
+    models::{
+        ArbitrageOpportunity, ArgusError, BlockRef, CexDetails, GasBreakdown, GasEstimateMethod,
+        OrderBook, ProfitPoint, RecommendedAction, ReferencePrice, Result, TradeSizeOptimization,
+        Venue,
+    },
+    retry::RetryPolicy,
     rpc::RpcClient,
+    utils::raw_to_decimal,
 };
 use chrono::Utc;
+use ethers::types::{Block, H256, U256};
+use futures::{FutureExt, StreamExt};
 use rust_decimal::Decimal;
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::Mutex;
-use tracing::info;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+// Golden-section iterations run after the coarse grid to narrow down the optimum
+const TRADE_SIZE_REFINEMENT_STEPS: usize = 8;
+
+// Validity window written into the router calldata used for gas estimation
+const SWAP_DEADLINE_SECS: i64 = 300;
+
+// Both pools are ETH (token0) against USDC (token1)
+const USDC_DECIMALS: u32 = 6;
+
+const ETH_DECIMALS: u32 = 18;
+const GWEI_DECIMALS: u32 = 9;
 
 pub struct ArbitrageService {
     eth_rpc: Arc<RpcClient>,
     base_rpc: Arc<RpcClient>,
     cex_client: Arc<dyn CexClient>,
+    cex_taker_fee_bps: u32,
+    cex_order_book_depth: usize,
     uniswap_client: Arc<dyn DexClient>,
     aerodrome_client: Arc<dyn DexClient>,
     analyzer: Arc<Mutex<ArbitrageAnalyzer>>,
+    guards: OpportunityGuards,
+    history: Arc<OpportunityHistory>,
+    eth_gas_pricing: GasPricing,
+    base_gas_pricing: GasPricing,
+    swap_recipient: ethers::types::Address,
+    max_slippage_bps: u32,
+    gas_estimation: GasEstimationMode,
+}
+
+/// Latest block of one chain, to which every read of an evaluation on that chain is pinned.
+#[derive(Debug, Clone, Copy)]
+struct PinnedBlock {
+    block: BlockRef,
+    base_fee_per_gas: U256,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PinnedBlocks {
+    ethereum: PinnedBlock,
+    base: PinnedBlock,
+}
+
+/// Gas cost of one swap leg and how its gas units were obtained.
+#[derive(Debug, Clone)]
+struct SwapGasCost {
+    usd: Decimal,
+    method: GasEstimateMethod,
+    breakdown: GasBreakdown,
+}
+
+/// How swaps on one chain are priced, from its `ChainConfig`.
+#[derive(Debug, Clone, Copy)]
+struct GasPricing {
+    units_buffer_percent: u32,
+    price_multiplier: Decimal,
+    priority_fee_percentile: Option<f64>,
+}
+
+impl TryFrom<&ChainConfig> for GasPricing {
+    type Error = ArgusError;
+
+    fn try_from(chain: &ChainConfig) -> Result<Self> {
+        Ok(Self {
+            units_buffer_percent: chain.gas_units_buffer_percent,
+            price_multiplier: Decimal::try_from(chain.gas_price_multiplier).map_err(|e| {
+                ArgusError::ConfigError(format!("Invalid gas price multiplier: {e}"))
+            })?,
+            priority_fee_percentile: chain.priority_fee_percentile,
+        })
+    }
 }
 
 impl ArbitrageService {
     pub async fn new(config: Config) -> Result<Self> {
         info!("Initializing Arbitrage Service");
 
-        let eth_rpc =
-            Arc::new(RpcClient::new(&config.ethereum.rpc_url, config.ethereum.chain_id).await?);
+        let health_check_interval =
+            Duration::from_secs(config.rpc.health_check_interval_secs.max(1));
+
+        let retry = RetryPolicy::from(&config.retry);
+
+        let eth_rpc = Arc::new(RpcClient::new(&config.ethereum, retry).await?);
+        eth_rpc.spawn_health_checks(health_check_interval);
         info!("Connected to Ethereum RPC");
 
-        let base_rpc = Arc::new(RpcClient::new(&config.base.rpc_url, config.base.chain_id).await?);
+        let base_rpc = Arc::new(RpcClient::new(&config.base, retry).await?);
+        base_rpc.spawn_health_checks(health_check_interval);
         info!("Connected to Base RPC");
 
-        let cex_client: Arc<dyn CexClient> = Arc::from(create_cex_client(&config.cex.provider));
+        let cex_client: Arc<dyn CexClient> = Arc::from(create_cex_client(&config.cex, retry));
         info!("CEX client initialized");
 
-        let uniswap_client = Arc::new(crate::dex::uniswap_v4::UniswapV4Client::new(
-            eth_rpc.clone(),
-        )?);
+        let pool_sync_interval = Duration::from_secs(config.monitor.poll_interval_secs.max(1));
+
+        let uniswap_client = Arc::new(Self::create_uniswap_client(&config, eth_rpc.clone())?);
+        spawn_pool_sync(uniswap_client.clone(), pool_sync_interval);
         info!("Uniswap V4 client initialized");
 
-        let aerodrome_client = Arc::new(crate::dex::aerodrome::AerodromeClient::new(
-            base_rpc.clone(),
-        )?);
-        info!("Aerodrome client initialized");
+        let aerodrome_client =
+            Self::create_aerodrome_client(&config, base_rpc.clone(), pool_sync_interval)?;
+
+        let swap_recipient = ethers::types::Address::from_str(&config.trading.swap_recipient)
+            .map_err(|e| ArgusError::ConfigError(format!("Invalid SWAP_RECIPIENT_ADDRESS: {e}")))?;
+        info!(
+            "Aerodrome {:?} client initialized",
+            config.aerodrome.pool_type
+        );
+
+        let history = Arc::new(OpportunityHistory::new(config.monitor.history_capacity));
+        Self::spawn_reorg_watcher(history.clone(), &eth_rpc);
+        Self::spawn_reorg_watcher(history.clone(), &base_rpc);
 
         Ok(Self {
             eth_rpc,
             base_rpc,
             cex_client,
+            cex_taker_fee_bps: config.cex.effective_taker_fee_bps(),
+            cex_order_book_depth: config.cex.order_book_depth,
             uniswap_client,
             aerodrome_client,
             analyzer: Arc::new(Mutex::new(ArbitrageAnalyzer::new())),
+            guards: OpportunityGuards::new(config.guards.clone()),
+            history,
+            eth_gas_pricing: GasPricing::try_from(&config.ethereum)?,
+            base_gas_pricing: GasPricing::try_from(&config.base)?,
+            swap_recipient,
+            max_slippage_bps: config.trading.max_slippage_bps,
+            gas_estimation: config.trading.gas_estimation,
         })
     }
 
+    fn create_uniswap_client(config: &Config, eth_rpc: Arc<RpcClient>) -> Result<UniswapV4Client> {
+        let hooks = ethers::types::Address::from_str(&config.uniswap_v4.hooks)
+            .map_err(|e| ArgusError::ConfigError(format!("Invalid uniswap_v4.hooks: {e}")))?;
+        let pool_key = PoolKey {
+            fee: config.uniswap_v4.fee,
+            tick_spacing: config.uniswap_v4.tick_spacing,
+            hooks,
+            ..PoolKey::new_weth_usdc()
+        };
+
+        UniswapV4Client::with_pool_key(eth_rpc, pool_key)
+    }
+
+    /// Builds the configured Aerodrome client; v2 pools also get an event-driven state
+    /// cache synced every `pool_sync_interval` when no block subscription is available.
+    fn create_aerodrome_client(
+        config: &Config,
+        base_rpc: Arc<RpcClient>,
+        pool_sync_interval: Duration,
+    ) -> Result<Arc<dyn DexClient>> {
+        use crate::dex::aerodrome::{AerodromeClient, SlipstreamClient};
+
+        let pool_address = config
+            .aerodrome
+            .pool_address
+            .as_deref()
+            .map(|address| {
+                ethers::types::Address::from_str(address).map_err(|e| {
+                    ArgusError::ConfigError(format!("Invalid AERODROME_POOL_ADDRESS: {e}"))
+                })
+            })
+            .transpose()?;
+
+        let client: Arc<dyn DexClient> = match (config.aerodrome.pool_type, pool_address) {
+            (AerodromePoolType::V2, address) => {
+                let client = Arc::new(match address {
+                    Some(address) => AerodromeClient::with_pool(base_rpc, address),
+                    None => AerodromeClient::new(base_rpc)?,
+                });
+                spawn_pool_sync(client.clone(), pool_sync_interval);
+                client
+            }
+            (AerodromePoolType::Slipstream, Some(address)) => {
+                Arc::new(SlipstreamClient::with_pool(base_rpc, address))
+            }
+            (AerodromePoolType::Slipstream, None) => Arc::new(SlipstreamClient::new(base_rpc)?),
+        };
+
+        Ok(client)
+    }
+
+    /// Marks recorded opportunities as invalidated when a reorg on `rpc`'s chain orphans
+    /// a block they were computed on.
+    fn spawn_reorg_watcher(history: Arc<OpportunityHistory>, rpc: &RpcClient) -> JoinHandle<()> {
+        let mut reorgs = rpc.subscribe_reorgs();
+        tokio::spawn(async move {
+            loop {
+                match reorgs.recv().await {
+                    Ok(event) => {
+                        let orphaned: Vec<H256> =
+                            event.orphaned.iter().map(|(_, hash)| *hash).collect();
+                        let invalidated = history.invalidate_blocks(&orphaned);
+                        if invalidated > 0 {
+                            warn!(
+                                "Reorg of depth {} on chain {} invalidated {} recorded opportunities",
+                                event.depth, event.chain_id, invalidated
+                            );
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Reorg watcher missed {} reorg events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+
+    #[must_use]
+    pub fn history(&self) -> Arc<OpportunityHistory> {
+        self.history.clone()
+    }
+
+    /// Spawns the background monitor, which re-evaluates every configured trade size
+    /// once per new block on either chain (or on every tick in interval mode) and records
+    /// the results in the opportunity history.
+    pub fn spawn_monitor(service: Arc<RwLock<Self>>, config: MonitorConfig) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            info!(
+                "Starting background monitor ({:?} trigger, {}s poll, sizes {:?})",
+                config.trigger, config.poll_interval_secs, config.trade_sizes_eth
+            );
+
+            match config.trigger {
+                MonitorTrigger::Block => Self::run_block_monitor(&service, &config).await,
+                MonitorTrigger::Interval => Self::run_interval_monitor(&service, &config).await,
+            }
+        })
+    }
+
+    async fn run_interval_monitor(service: &RwLock<Self>, config: &MonitorConfig) {
+        let mut ticker =
+            tokio::time::interval(Duration::from_secs(config.poll_interval_secs.max(1)));
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        loop {
+            ticker.tick().await;
+            service
+                .read()
+                .await
+                .run_monitor_cycle(&config.trade_sizes_eth)
+                .await;
+        }
+    }
+
+    /// Follows the block streams of both chains. Blocks that arrive while a cycle is
+    /// running are coalesced, so each cycle evaluates the newest state exactly once.
+    async fn run_block_monitor(service: &RwLock<Self>, config: &MonitorConfig) {
+        let poll_interval = Duration::from_secs(config.poll_interval_secs.max(1));
+        let (eth_rpc, base_rpc) = {
+            let service = service.read().await;
+            (service.eth_rpc.clone(), service.base_rpc.clone())
+        };
+        info!(
+            "Monitor block source - Ethereum: {}, Base: {}",
+            if eth_rpc.supports_subscriptions() {
+                "newHeads"
+            } else {
+                "polling"
+            },
+            if base_rpc.supports_subscriptions() {
+                "newHeads"
+            } else {
+                "polling"
+            }
+        );
+
+        let mut last_seen: HashMap<u64, H256> = HashMap::new();
+
+        loop {
+            let streams = tokio::try_join!(
+                eth_rpc.new_blocks(poll_interval),
+                base_rpc.new_blocks(poll_interval)
+            );
+            let (eth_blocks, base_blocks) = match streams {
+                Ok(streams) => streams,
+                Err(e) => {
+                    warn!("Monitor failed to open block streams: {}", e);
+                    tokio::time::sleep(poll_interval).await;
+                    continue;
+                }
+            };
+
+            let eth_chain_id = eth_rpc.chain_id();
+            let base_chain_id = base_rpc.chain_id();
+            let mut blocks = futures::stream::select(
+                eth_blocks.map(move |block| (eth_chain_id, block)),
+                base_blocks.map(move |block| (base_chain_id, block)),
+            );
+
+            while let Some(event) = blocks.next().await {
+                let mut is_new = Self::track_block(&mut last_seen, event);
+                while let Some(Some(event)) = blocks.next().now_or_never() {
+                    is_new |= Self::track_block(&mut last_seen, event);
+                }
+
+                if is_new {
+                    service
+                        .read()
+                        .await
+                        .run_monitor_cycle(&config.trade_sizes_eth)
+                        .await;
+                }
+            }
+
+            warn!("Block streams ended, resubscribing");
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Records a block from the stream, returning whether it was not seen before.
+    fn track_block(
+        last_seen: &mut HashMap<u64, H256>,
+        (chain_id, block): (u64, Result<Block<H256>>),
+    ) -> bool {
+        match block {
+            Ok(Block {
+                hash: Some(hash),
+                number,
+                ..
+            }) => {
+                let is_new = last_seen.insert(chain_id, hash) != Some(hash);
+                if is_new {
+                    debug!("New block on chain {}: {:?} ({:?})", chain_id, number, hash);
+                }
+                is_new
+            }
+            Ok(_) => false,
+            Err(e) => {
+                warn!("Block stream error on chain {}: {}", chain_id, e);
+                false
+            }
+        }
+    }
+
+    async fn run_monitor_cycle(&self, trade_sizes_eth: &[Decimal]) {
+        for trade_size_eth in trade_sizes_eth {
+            match self.check_arbitrage_opportunity(*trade_size_eth).await {
+                Ok(opportunity) => self.history.record(opportunity),
+                Err(e) => warn!(
+                    "Monitor failed to evaluate {} ETH trade: {}",
+                    trade_size_eth, e
+                ),
+            }
+        }
+    }
+
     pub async fn check_arbitrage_opportunity(
         &self,
         trade_size_eth: Decimal,
     ) -> Result<ArbitrageOpportunity> {
+        let result = self.evaluate_opportunity(trade_size_eth).await;
+        match &result {
+            Ok(opportunity) => {
+                Metrics::global().record_opportunity(opportunity, self.cex_client.name());
+            }
+            Err(e) => Metrics::global().record_error(e),
+        }
+        result
+    }
+
+    async fn evaluate_opportunity(&self, trade_size_eth: Decimal) -> Result<ArbitrageOpportunity> {
         info!("Checking arbitrage opportunity for {} ETH", trade_size_eth);
 
-        let (cex_price, uniswap_quote, aerodrome_quote) = tokio::try_join!(
+        let blocks = self.pin_blocks().await?;
+        let (cex_price, order_book, round_trip, pool_liquidity) = tokio::try_join!(
             self.fetch_cex_price(),
-            self.get_uniswap_quote(trade_size_eth),
-            self.get_aerodrome_quote(trade_size_eth)
+            self.fetch_cex_order_book().map(Ok::<_, ArgusError>),
+            self.quote_round_trip(trade_size_eth, &blocks),
+            self.fetch_pool_liquidity(&blocks)
         )?;
+        let cex_routes = self
+            .quote_cex_routes(trade_size_eth, order_book.as_ref(), &blocks)
+            .await?;
 
         self.analyzer
             .lock()
             .unwrap()
             .update_eth_price(cex_price.price);
 
-        let uniswap_swap_calldata = Self::build_uniswap_swap_calldata(trade_size_eth);
-        let aerodrome_swap_calldata = Self::build_aerodrome_swap_calldata(trade_size_eth);
-
-        let eth_gas_cost_usd = self
-            .estimate_gas_usd_eth_swap(uniswap_swap_calldata.clone(), cex_price.price)
-            .await?;
-
-        let base_gas_cost_usd = self
-            .estimate_gas_usd_base_swap(aerodrome_swap_calldata.clone(), cex_price.price)
+        let (eth_gas_cost, base_gas_cost) = self
+            .estimate_gas_costs(&round_trip, cex_price.price, &blocks)
             .await?;
 
-        info!(
-            "Gas cost in USD - ETH: ${:.4}, Base total: ${:.4}",
-            eth_gas_cost_usd, base_gas_cost_usd
-        );
+        // A reorg during the evaluation may already have orphaned the pinned blocks
+        let (eth_block, base_block) = (blocks.ethereum.block, blocks.base.block);
+        let invalidated = !(self
+            .eth_rpc
+            .is_canonical(eth_block.number, eth_block.hash)
+            .await
+            && self
+                .base_rpc
+                .is_canonical(base_block.number, base_block.hash)
+                .await);
 
         let analyzer = self.analyzer.lock().unwrap();
-        let arbitrage_summary = analyzer.analyze_opportunity_with_gas(
-            &uniswap_quote,
-            &aerodrome_quote,
-            trade_size_eth,
-            cex_price.price,
-            eth_gas_cost_usd,
-            base_gas_cost_usd,
+        let mut routes = analyzer.analyze_opportunity_with_gas(
+            &round_trip,
+            &cex_routes,
+            eth_gas_cost.usd,
+            base_gas_cost.usd,
         )?;
+        let uniswap_v4_details = analyzer.create_dex_details(
+            round_trip.quote_for(Venue::UniswapV4),
+            eth_gas_cost.usd,
+            eth_gas_cost.method,
+            eth_gas_cost.breakdown,
+        );
+        let aerodrome_details = analyzer.create_dex_details(
+            round_trip.quote_for(Venue::Aerodrome),
+            base_gas_cost.usd,
+            base_gas_cost.method,
+            base_gas_cost.breakdown,
+        );
+        drop(analyzer);
+
+        let warnings = self.guards.check(
+            &cex_price,
+            &[
+                (Venue::UniswapV4, uniswap_v4_details.effective_price_usd),
+                (Venue::Aerodrome, aerodrome_details.effective_price_usd),
+            ],
+            &pool_liquidity,
+            Utc::now(),
+        );
+        self.guards.apply(&warnings, &mut routes);
+
+        let cex_details = order_book.as_ref().and_then(|book| {
+            Some(CexDetails {
+                exchange: book.exchange.clone(),
+                best_bid_usd: book.best_bid()?,
+                best_ask_usd: book.best_ask()?,
+                taker_fee_bps: self.cex_taker_fee_bps,
+            })
+        });
 
         let opportunity = ArbitrageOpportunity {
             timestamp_utc: Utc::now(),
             trade_size_eth,
             reference_cex_price_usd: cex_price.price,
-            uniswap_v4_details: analyzer.create_dex_details(&uniswap_quote, eth_gas_cost_usd),
-            aerodrome_details: analyzer.create_dex_details(&aerodrome_quote, base_gas_cost_usd),
-            arbitrage_summary,
+            reference_cex_quotes: cex_price.quotes,
+            reference_cex_spread_bps: cex_price.spread_bps,
+            uniswap_v4_details,
+            aerodrome_details,
+            cex_details,
+            arbitrage_summary: routes[0].clone(),
+            routes,
+            ethereum_block: blocks.ethereum.block,
+            base_block: blocks.base.block,
+            invalidated,
+            warnings,
         };
 
         info!(
-            "Arbitrage check completed: {:?}",
+            "Arbitrage check completed at Ethereum block {} / Base block {}: buy on {:?}, sell on {:?} -> {:?}",
+            opportunity.ethereum_block.number,
+            opportunity.base_block.number,
+            opportunity.arbitrage_summary.buy_venue,
+            opportunity.arbitrage_summary.sell_venue,
             opportunity.arbitrage_summary.recommended_action
         );
 
         Ok(opportunity)
     }
 
-    async fn fetch_cex_price(&self) -> Result<crate::models::CexPrice> {
-        self.cex_client.get_spot_price("ETH", "USDC").await
+    /// Searches `[min_trade_size_eth, max_trade_size_eth]` for the trade size with the
+    /// highest net profit. A geometric grid of `samples` points locates the best region,
+    /// which is then refined with a golden-section search.
+    pub async fn find_optimal_trade_size(
+        &self,
+        min_trade_size_eth: Decimal,
+        max_trade_size_eth: Decimal,
+        samples: usize,
+    ) -> Result<TradeSizeOptimization> {
+        let result = self
+            .search_trade_size(min_trade_size_eth, max_trade_size_eth, samples)
+            .await;
+        if let Err(e) = &result {
+            Metrics::global().record_error(e);
+        }
+        result
     }
 
-    async fn get_uniswap_quote(&self, amount_eth: Decimal) -> Result<SwapQuote> {
-        self.uniswap_client
-            .calculate_swap_output(amount_eth, true)
-            .await
+    async fn search_trade_size(
+        &self,
+        min_trade_size_eth: Decimal,
+        max_trade_size_eth: Decimal,
+        samples: usize,
+    ) -> Result<TradeSizeOptimization> {
+        if min_trade_size_eth <= Decimal::ZERO || max_trade_size_eth <= min_trade_size_eth {
+            return Err(ArgusError::CalculationError(format!(
+                "Invalid trade size range: {min_trade_size_eth} - {max_trade_size_eth}"
+            )));
+        }
+        if samples < 3 {
+            return Err(ArgusError::CalculationError(
+                "At least 3 samples are required to search for an optimum".to_string(),
+            ));
+        }
+
+        info!(
+            "Searching optimal trade size between {} and {} ETH ({} samples)",
+            min_trade_size_eth, max_trade_size_eth, samples
+        );
+
+        let (cex_price, order_book, blocks) = tokio::try_join!(
+            self.fetch_cex_price(),
+            self.fetch_cex_order_book().map(Ok::<_, ArgusError>),
+            self.pin_blocks()
+        )?;
+        let order_book = order_book.as_ref();
+        self.analyzer
+            .lock()
+            .unwrap()
+            .update_eth_price(cex_price.price);
+
+        // Gas barely depends on the amount, so the smallest trade's calldata prices every
+        // point; it is also the size most likely to be fillable
+        let reference_round_trip = self.quote_round_trip(min_trade_size_eth, &blocks).await?;
+        let (eth_gas_cost, base_gas_cost) = self
+            .estimate_gas_costs(&reference_round_trip, cex_price.price, &blocks)
+            .await?;
+        let (eth_gas_cost_usd, base_gas_cost_usd) = (eth_gas_cost.usd, base_gas_cost.usd);
+        let total_gas_cost_usd = eth_gas_cost_usd + base_gas_cost_usd;
+
+        let grid = Self::geometric_grid(min_trade_size_eth, max_trade_size_eth, samples)?;
+        let results = futures::future::join_all(grid.iter().map(|size| {
+            self.evaluate_trade_size(
+                *size,
+                &blocks,
+                order_book,
+                eth_gas_cost_usd,
+                base_gas_cost_usd,
+            )
+        }))
+        .await;
+
+        // A size the pools cannot fill drops out of the curve instead of failing the search
+        let mut curve = Vec::with_capacity(grid.len());
+        let mut first_error = None;
+        for (size, result) in grid.iter().zip(results) {
+            match result {
+                Ok(point) => curve.push(point),
+                Err(e) => {
+                    warn!("Skipping trade size {} ETH: {}", size, e);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        if curve.is_empty() {
+            return Err(first_error.unwrap_or_else(|| {
+                ArgusError::CalculationError("No trade size could be evaluated".to_string())
+            }));
+        }
+
+        let best_size = curve[Self::best_point_index(&curve)].trade_size_eth;
+        let best_index = grid.iter().position(|size| *size == best_size).unwrap_or(0);
+        let refined = self
+            .refine_trade_size(
+                grid[best_index.saturating_sub(1)],
+                grid[(best_index + 1).min(grid.len() - 1)],
+                &blocks,
+                order_book,
+                eth_gas_cost_usd,
+                base_gas_cost_usd,
+            )
+            .await;
+        curve.extend(refined);
+
+        curve.sort_by_key(|point| point.trade_size_eth);
+        curve.dedup_by(|a, b| a.trade_size_eth == b.trade_size_eth);
+
+        let optimal = curve[Self::best_point_index(&curve)].clone();
+        let recommended_action = if optimal.net_profit_usd > Decimal::ZERO {
+            RecommendedAction::ArbitrageDetected
+        } else {
+            RecommendedAction::NoArbitrage
+        };
+
+        info!(
+            "Optimal trade size: {} ETH with net profit ${:.2} ({} points evaluated)",
+            optimal.trade_size_eth,
+            optimal.net_profit_usd,
+            curve.len()
+        );
+
+        Ok(TradeSizeOptimization {
+            timestamp_utc: Utc::now(),
+            reference_cex_price_usd: cex_price.price,
+            total_gas_cost_usd,
+            optimal,
+            recommended_action,
+            profit_curve: curve,
+            ethereum_block: blocks.ethereum.block,
+            base_block: blocks.base.block,
+        })
     }
 
-    async fn get_aerodrome_quote(&self, amount_eth: Decimal) -> Result<SwapQuote> {
-        self.aerodrome_client
-            .calculate_swap_output(amount_eth, true)
-            .await
+    /// Golden-section search for the most profitable size between `low` and `high`,
+    /// returning every point it evaluated. Stops early at the first size that fails.
+    async fn refine_trade_size(
+        &self,
+        mut low: Decimal,
+        mut high: Decimal,
+        blocks: &PinnedBlocks,
+        order_book: Option<&OrderBook>,
+        eth_gas_cost_usd: Decimal,
+        base_gas_cost_usd: Decimal,
+    ) -> Vec<ProfitPoint> {
+        let mut points = Vec::new();
+        let ratio = Decimal::from_str("0.6180339887").unwrap();
+        for _ in 0..TRADE_SIZE_REFINEMENT_STEPS {
+            let span = high - low;
+            let left = (high - span * ratio).round_dp(6);
+            let right = (low + span * ratio).round_dp(6);
+            if left >= right {
+                break;
+            }
+
+            let evaluated = tokio::try_join!(
+                self.evaluate_trade_size(
+                    left,
+                    blocks,
+                    order_book,
+                    eth_gas_cost_usd,
+                    base_gas_cost_usd
+                ),
+                self.evaluate_trade_size(
+                    right,
+                    blocks,
+                    order_book,
+                    eth_gas_cost_usd,
+                    base_gas_cost_usd
+                )
+            );
+            let (left_point, right_point) = match evaluated {
+                Ok(points) => points,
+                Err(e) => {
+                    warn!(
+                        "Stopping trade size refinement between {} and {} ETH: {}",
+                        left, right, e
+                    );
+                    break;
+                }
+            };
+
+            if left_point.net_profit_usd >= right_point.net_profit_usd {
+                high = right;
+            } else {
+                low = left;
+            }
+            points.push(left_point);
+            points.push(right_point);
+        }
+
+        points
     }
 
-    fn build_uniswap_swap_calldata(_trade_size_eth: Decimal) -> Vec<u8> {
-        let mut calldata = Vec::new();
-        calldata.extend_from_slice(&[0x12, 0x34, 0x56, 0x78]);
-        calldata.extend_from_slice(&[0xAA; 200]);
+    async fn evaluate_trade_size(
+        &self,
+        trade_size_eth: Decimal,
+        blocks: &PinnedBlocks,
+        order_book: Option<&OrderBook>,
+        eth_gas_cost_usd: Decimal,
+        base_gas_cost_usd: Decimal,
+    ) -> Result<ProfitPoint> {
+        let (round_trip, cex_routes) = tokio::try_join!(
+            self.quote_round_trip(trade_size_eth, blocks),
+            self.quote_cex_routes(trade_size_eth, order_book, blocks)
+        )?;
+
+        let routes = self.analyzer.lock().unwrap().analyze_opportunity_with_gas(
+            &round_trip,
+            &cex_routes,
+            eth_gas_cost_usd,
+            base_gas_cost_usd,
+        )?;
+        let summary = &routes[0];
 
-        calldata
+        Ok(ProfitPoint {
+            trade_size_eth,
+            buy_venue: summary.buy_venue,
+            sell_venue: summary.sell_venue,
+            potential_profit_usd: summary.potential_profit_usd,
+            net_profit_usd: summary.net_profit_usd,
+        })
     }
 
-    fn build_aerodrome_swap_calldata(_trade_size_eth: Decimal) -> Vec<u8> {
-        let mut calldata = Vec::new();
-        calldata.extend_from_slice(&[0x87, 0x65, 0x43, 0x21]);
-        calldata.extend_from_slice(&[0xBB; 180]);
+    fn geometric_grid(min: Decimal, max: Decimal, samples: usize) -> Result<Vec<Decimal>> {
+        let to_f64 = |value: Decimal| {
+            value.to_string().parse::<f64>().map_err(|e| {
+                ArgusError::CalculationError(format!("Failed to convert trade size: {e}"))
+            })
+        };
+        let (min_f, max_f) = (to_f64(min)?, to_f64(max)?);
+
+        #[allow(clippy::cast_precision_loss)]
+        let step = (max_f / min_f).powf(1.0 / (samples - 1) as f64);
 
-        calldata
+        let mut grid = Vec::with_capacity(samples);
+        let mut size = min_f;
+        for _ in 0..samples - 1 {
+            let point = Decimal::try_from(size)
+                .map_err(|e| {
+                    ArgusError::CalculationError(format!("Failed to convert trade size: {e}"))
+                })?
+                .round_dp(6);
+            grid.push(point.clamp(min, max));
+            size *= step;
+        }
+        grid.push(max);
+        grid.dedup();
+
+        Ok(grid)
     }
 
-    async fn estimate_gas_usd_eth_swap(
+    fn best_point_index(curve: &[ProfitPoint]) -> usize {
+        curve
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.net_profit_usd.cmp(&b.net_profit_usd))
+            .map_or(0, |(index, _)| index)
+    }
+
+    async fn estimate_gas_costs(
         &self,
-        _calldata: Vec<u8>,
+        round_trip: &RoundTripQuote,
         eth_price_usd: Decimal,
-    ) -> Result<Decimal> {
-        let latest_block = self.eth_rpc.get_latest_block().await?;
-        let base_fee_per_gas = latest_block.base_fee_per_gas.ok_or_else(|| {
-            crate::models::ArgusError::RpcError("Cannot get base fee from RPC".to_string())
-        })?;
+        blocks: &PinnedBlocks,
+    ) -> Result<(SwapGasCost, SwapGasCost)> {
+        let (uniswap_swap_calldata, aerodrome_swap_calldata) = tokio::try_join!(
+            self.build_swap_calldata(round_trip, Venue::UniswapV4),
+            self.build_swap_calldata(round_trip, Venue::Aerodrome)
+        )?;
+
+        let (eth_gas_cost, base_gas_cost) = tokio::try_join!(
+            self.estimate_gas_usd_eth_swap(&uniswap_swap_calldata, eth_price_usd, &blocks.ethereum),
+            self.estimate_gas_usd_base_swap(&aerodrome_swap_calldata, eth_price_usd, &blocks.base)
+        )?;
 
-        let priority_fee =
-            ethers::types::U256::from(self.eth_rpc.get_max_priority_fee_per_gas().await?);
+        info!(
+            "Gas cost in USD - ETH: ${:.4} ({:?}), Base total: ${:.4} ({:?})",
+            eth_gas_cost.usd, eth_gas_cost.method, base_gas_cost.usd, base_gas_cost.method
+        );
 
-        let gas_price_wei = base_fee_per_gas + priority_fee;
+        Ok((eth_gas_cost, base_gas_cost))
+    }
 
-        let gas_estimate_raw = self.eth_rpc.get_typical_swap_gas()?;
+    /// Gas units for `calldata`: simulated with `eth_estimateGas` at `block_number` when
+    /// enabled, falling back to the chain's typical swap gas if the simulation fails.
+    async fn swap_gas_units(
+        &self,
+        rpc: &RpcClient,
+        calldata: &SwapCalldata,
+        block_number: u64,
+    ) -> Result<(u64, GasEstimateMethod)> {
+        if let GasEstimationMode::Simulate = self.gas_estimation {
+            let tx = ethers::types::TransactionRequest::new()
+                .from(calldata.from)
+                .to(calldata.to)
+                .data(calldata.data.clone())
+                .value(calldata.value);
 
-        let gas_with_buffer = ethers::types::U256::from(gas_estimate_raw) * 110 / 100;
+            match rpc
+                .estimate_gas_with_overrides(&tx, &calldata.state_overrides, block_number)
+                .await
+            {
+                Ok(gas) => return Ok((gas, GasEstimateMethod::EthEstimateGas)),
+                Err(e) => {
+                    Metrics::global().record_error(&e);
+                    warn!(
+                        "Gas simulation failed on chain {}, using typical swap gas: {}",
+                        rpc.chain_id(),
+                        e
+                    );
+                }
+            }
+        }
 
-        let cost_wei: ethers::types::U256 = gas_with_buffer * gas_price_wei;
+        Ok((
+            rpc.get_typical_swap_gas(),
+            GasEstimateMethod::TypicalConstant,
+        ))
+    }
 
-        let cost_eth = Decimal::from_str(&cost_wei.to_string()).map_err(|e| {
-            crate::models::ArgusError::CalculationError(format!("U256 conversion error: {e}"))
-        })? / Decimal::from_str("1000000000000000000").unwrap();
+    /// Router calldata for the round-trip leg executed on `venue`, with the minimum output
+    /// set `max_slippage_bps` below the quoted amount.
+    async fn build_swap_calldata(
+        &self,
+        round_trip: &RoundTripQuote,
+        venue: Venue,
+    ) -> Result<SwapCalldata> {
+        let (amount_in, quoted_out, zero_for_one) = if venue == round_trip.buy_venue {
+            (round_trip.usdc_in, round_trip.eth_bought, false)
+        } else {
+            (round_trip.eth_bought, round_trip.usdc_out, true)
+        };
 
-        let cost_usd = cost_eth * eth_price_usd;
+        let slippage = Decimal::from(self.max_slippage_bps) / Decimal::from(10_000);
+        let deadline = u64::try_from(Utc::now().timestamp() + SWAP_DEADLINE_SECS).unwrap_or(0);
 
-        #[allow(clippy::cast_precision_loss)]
-        let price_in_gwei = gas_price_wei.as_u128() as f64 / 1e9;
-        info!(
-            "ETH swap: raw_gas={}, buffered_gas={}, gas_price={:.3} gwei, cost=${:.4}",
-            gas_estimate_raw, gas_with_buffer, price_in_gwei, cost_usd
-        );
+        self.dex_client(venue)?
+            .build_swap_calldata(&SwapCalldataParams {
+                amount_in,
+                min_amount_out: quoted_out * (Decimal::ONE - slippage),
+                zero_for_one,
+                recipient: self.swap_recipient,
+                deadline,
+            })
+            .await
+    }
 
-        Ok(cost_usd)
+    fn dex_client(&self, venue: Venue) -> Result<&dyn DexClient> {
+        match venue {
+            Venue::UniswapV4 => Ok(self.uniswap_client.as_ref()),
+            Venue::Aerodrome => Ok(self.aerodrome_client.as_ref()),
+            Venue::Cex => Err(ArgusError::CalculationError(
+                "The CEX has no swap calldata".to_string(),
+            )),
+        }
     }
 
-    async fn estimate_gas_usd_base_swap(
+    async fn fetch_cex_price(&self) -> Result<ReferencePrice> {
+        self.cex_client.get_reference_price("ETH", "USDC").await
+    }
+
+    /// ETH/USDC order book for pricing CEX legs. A failed fetch only drops the CEX routes
+    /// from the evaluation, so it is logged rather than returned.
+    async fn fetch_cex_order_book(&self) -> Option<OrderBook> {
+        self.cex_client
+            .get_order_book("ETH", "USDC", self.cex_order_book_depth)
+            .await
+            .map_err(|e| {
+                Metrics::global().record_error(&e);
+                warn!(
+                    "Failed to fetch {} order book, skipping CEX routes: {}",
+                    self.cex_client.name(),
+                    e
+                );
+            })
+            .ok()
+    }
+
+    /// In-range liquidity of each pool in USDC at the pinned blocks; only read while the
+    /// liquidity guard is enabled.
+    async fn fetch_pool_liquidity(&self, blocks: &PinnedBlocks) -> Result<Vec<(Venue, Decimal)>> {
+        if !self.guards.checks_pool_liquidity() {
+            return Ok(Vec::new());
+        }
+
+        let (uniswap, aerodrome) = tokio::try_join!(
+            self.uniswap_client
+                .get_pool_state(blocks.ethereum.block.number),
+            self.aerodrome_client
+                .get_pool_state(blocks.base.block.number)
+        )?;
+
+        Ok(vec![
+            (
+                Venue::UniswapV4,
+                uniswap.active_liquidity_in_token1(USDC_DECIMALS)?,
+            ),
+            (
+                Venue::Aerodrome,
+                aerodrome.active_liquidity_in_token1(USDC_DECIMALS)?,
+            ),
+        ])
+    }
+
+    /// Latest block of each chain; an evaluation reads all on-chain state at these blocks.
+    async fn pin_blocks(&self) -> Result<PinnedBlocks> {
+        let (ethereum, base) = tokio::try_join!(
+            self.eth_rpc.get_latest_block(),
+            self.base_rpc.get_latest_block()
+        )?;
+
+        let pin = |block: &Block<H256>, chain: &str| -> Result<PinnedBlock> {
+            Ok(PinnedBlock {
+                block: BlockRef::try_from(block)?,
+                base_fee_per_gas: block.base_fee_per_gas.ok_or_else(|| {
+                    ArgusError::RpcError(format!("Cannot get base fee from {chain} RPC"))
+                })?,
+            })
+        };
+
+        Ok(PinnedBlocks {
+            ethereum: pin(&ethereum, "Ethereum")?,
+            base: pin(&base, "Base")?,
+        })
+    }
+
+    async fn quote_round_trip(
         &self,
-        calldata: Vec<u8>,
-        eth_price_usd: Decimal,
-    ) -> Result<Decimal> {
-        let latest_block = self.base_rpc.get_latest_block().await?;
-        let base_fee_per_gas = latest_block.base_fee_per_gas.ok_or_else(|| {
-            crate::models::ArgusError::RpcError("Cannot get base fee from Base RPC".to_string())
-        })?;
+        trade_size_eth: Decimal,
+        blocks: &PinnedBlocks,
+    ) -> Result<RoundTripQuote> {
+        ArbitrageAnalyzer::quote_round_trip(
+            self.uniswap_client.as_ref(),
+            self.aerodrome_client.as_ref(),
+            trade_size_eth,
+            blocks.ethereum.block.number,
+            blocks.base.block.number,
+        )
+        .await
+    }
+
+    /// Routes between the CEX and each DEX at the pinned blocks; none without an order book.
+    async fn quote_cex_routes(
+        &self,
+        trade_size_eth: Decimal,
+        order_book: Option<&OrderBook>,
+        blocks: &PinnedBlocks,
+    ) -> Result<Vec<RouteQuote>> {
+        let Some(order_book) = order_book else {
+            return Ok(Vec::new());
+        };
 
-        let priority_fee =
-            ethers::types::U256::from(self.base_rpc.get_max_priority_fee_per_gas().await?);
+        let (uniswap_routes, aerodrome_routes) = tokio::try_join!(
+            ArbitrageAnalyzer::quote_cex_routes(
+                self.uniswap_client.as_ref(),
+                Venue::UniswapV4,
+                order_book,
+                self.cex_taker_fee_bps,
+                trade_size_eth,
+                blocks.ethereum.block.number,
+            ),
+            ArbitrageAnalyzer::quote_cex_routes(
+                self.aerodrome_client.as_ref(),
+                Venue::Aerodrome,
+                order_book,
+                self.cex_taker_fee_bps,
+                trade_size_eth,
+                blocks.base.block.number,
+            )
+        )?;
 
-        let l2_gas_price_wei = base_fee_per_gas + priority_fee;
+        Ok(uniswap_routes.into_iter().chain(aerodrome_routes).collect())
+    }
 
-        let l2_gas_estimate_raw = self.base_rpc.get_typical_swap_gas()?;
+    async fn estimate_gas_usd_eth_swap(
+        &self,
+        calldata: &SwapCalldata,
+        eth_price_usd: Decimal,
+        block: &PinnedBlock,
+    ) -> Result<SwapGasCost> {
+        let cost = self
+            .swap_gas_cost(
+                &self.eth_rpc,
+                &self.eth_gas_pricing,
+                calldata,
+                eth_price_usd,
+                block,
+                U256::zero(),
+            )
+            .await?;
 
-        let l2_gas_with_buffer = ethers::types::U256::from(l2_gas_estimate_raw) * 110 / 100;
+        let breakdown = &cost.breakdown;
+        info!(
+            "ETH swap: raw_gas={}, buffered_gas={}, gas_price={:.3} gwei (base {:.3} + priority {:.3}, x{}), cost=${:.4}",
+            breakdown.estimated_gas_units,
+            breakdown.buffered_gas_units,
+            breakdown.gas_price_gwei,
+            breakdown.base_fee_gwei,
+            breakdown.priority_fee_gwei,
+            breakdown.gas_price_multiplier,
+            cost.usd
+        );
 
-        let l2_cost_wei: ethers::types::U256 = l2_gas_with_buffer * l2_gas_price_wei;
+        Ok(cost)
+    }
 
-        let dummy_address: ethers::types::Address = ethers::types::Address::zero();
-        let l1_data_fee_wei: ethers::types::U256 = ethers::types::U256::from(
+    async fn estimate_gas_usd_base_swap(
+        &self,
+        calldata: &SwapCalldata,
+        eth_price_usd: Decimal,
+        block: &PinnedBlock,
+    ) -> Result<SwapGasCost> {
+        let l1_data_fee_wei = U256::from(
             self.base_rpc
-                .estimate_l1_data_fee(dummy_address, calldata)
+                .estimate_l1_data_fee(block.block.number, calldata.to, calldata.data.clone())
                 .await?,
         );
 
-        let total_cost_wei: ethers::types::U256 = l2_cost_wei + l1_data_fee_wei;
+        let cost = self
+            .swap_gas_cost(
+                &self.base_rpc,
+                &self.base_gas_pricing,
+                calldata,
+                eth_price_usd,
+                block,
+                l1_data_fee_wei,
+            )
+            .await?;
+
+        let breakdown = &cost.breakdown;
+        info!(
+            "Base swap: l2_raw_gas={}, l2_buffered={}, l2_price={:.3} gwei (base {:.3} + priority {:.3}, x{}), l1_fee={:.6} ETH, total=${:.4}",
+            breakdown.estimated_gas_units,
+            breakdown.buffered_gas_units,
+            breakdown.gas_price_gwei,
+            breakdown.base_fee_gwei,
+            breakdown.priority_fee_gwei,
+            breakdown.gas_price_multiplier,
+            breakdown.l1_fee_eth,
+            cost.usd
+        );
+
+        Ok(cost)
+    }
 
-        let total_cost_eth = Decimal::from_str(&total_cost_wei.to_string()).map_err(|e| {
-            crate::models::ArgusError::CalculationError(format!("U256 conversion error: {e}"))
-        })? / Decimal::from_str("1000000000000000000").unwrap();
+    /// Gas cost of the swap in `calldata` at `block`: its gas units plus the chain's
+    /// buffer, priced at base fee plus priority fee times the chain's multiplier, plus
+    /// `l1_fee_wei` for swaps on an L2.
+    async fn swap_gas_cost(
+        &self,
+        rpc: &RpcClient,
+        pricing: &GasPricing,
+        calldata: &SwapCalldata,
+        eth_price_usd: Decimal,
+        block: &PinnedBlock,
+        l1_fee_wei: U256,
+    ) -> Result<SwapGasCost> {
+        let priority_fee = async {
+            match pricing.priority_fee_percentile {
+                Some(percentile) => {
+                    rpc.get_priority_fee_percentile(block.block.number, percentile)
+                        .await
+                }
+                None => rpc.get_max_priority_fee_per_gas().await,
+            }
+        };
+        let (priority_fee, (estimated_gas_units, method)) = tokio::try_join!(
+            priority_fee,
+            self.swap_gas_units(rpc, calldata, block.block.number)
+        )?;
 
-        let l2_cost_eth = Decimal::from_str(&l2_cost_wei.to_string()).unwrap()
-            / Decimal::from_str("1000000000000000000").unwrap();
-        let l1_data_fee_eth = Decimal::from_str(&l1_data_fee_wei.to_string()).unwrap()
-            / Decimal::from_str("1000000000000000000").unwrap();
+        let buffered_gas_units =
+            estimated_gas_units * (100 + u64::from(pricing.units_buffer_percent)) / 100;
 
-        let total_cost_usd = total_cost_eth * eth_price_usd;
+        let base_fee_gwei = raw_to_decimal(block.base_fee_per_gas, GWEI_DECIMALS)?;
+        let priority_fee_gwei = raw_to_decimal(U256::from(priority_fee), GWEI_DECIMALS)?;
+        let gas_price_gwei = (base_fee_gwei + priority_fee_gwei) * pricing.price_multiplier;
 
-        #[allow(clippy::cast_precision_loss)]
-        let l2_price_in_gwei = l2_gas_price_wei.as_u128() as f64 / 1e9;
-        info!("Base swap: l2_raw_gas={}, l2_buffered={}, l2_price={:.3} gwei, l2_cost={:.6} ETH, l1_fee={:.6} ETH, total=${:.4}", 
-              l2_gas_estimate_raw, l2_gas_with_buffer, l2_price_in_gwei,
-              l2_cost_eth, l1_data_fee_eth, total_cost_usd);
+        let l1_fee_eth = raw_to_decimal(l1_fee_wei, ETH_DECIMALS)?;
+        let execution_cost_eth = Decimal::from(buffered_gas_units) * gas_price_gwei
+            / Decimal::from(10u64.pow(ETH_DECIMALS - GWEI_DECIMALS));
+        let total_cost_eth = execution_cost_eth + l1_fee_eth;
 
-        Ok(total_cost_usd)
+        Ok(SwapGasCost {
+            usd: total_cost_eth * eth_price_usd,
+            method,
+            breakdown: GasBreakdown {
+                base_fee_gwei,
+                priority_fee_gwei,
+                gas_price_multiplier: pricing.price_multiplier,
+                gas_price_gwei,
+                estimated_gas_units,
+                buffered_gas_units,
+                l1_fee_eth,
+                total_cost_eth,
+            },
+        })
     }
 }
@@ -3,6 +3,7 @@
  */
 
 use crate::models::{ArgusError, Result};
+use ethers::types::U256;
 use num_bigint::BigUint;
 use rust_decimal::Decimal;
 use std::str::FromStr;
@@ -59,6 +60,30 @@ pub fn calculate_price_impact(
     -((expected_out - amount_out) / expected_out * Decimal::from(100))
 }
 
+/// Converts a human-readable token amount into its raw integer representation.
+pub fn decimal_to_raw(amount: Decimal, decimals: u32) -> Result<U256> {
+    if amount.is_sign_negative() {
+        return Err(ArgusError::CalculationError(format!(
+            "Negative token amount: {amount}"
+        )));
+    }
+
+    let scaled = amount
+        .checked_mul(Decimal::from(10u64.pow(decimals)))
+        .ok_or_else(|| ArgusError::CalculationError(format!("Amount too large: {amount}")))?
+        .trunc();
+
+    U256::from_dec_str(&scaled.to_string())
+        .map_err(|e| ArgusError::CalculationError(format!("Failed to parse amount: {e}")))
+}
+
+/// Converts a raw integer token amount into a human-readable decimal.
+pub fn raw_to_decimal(raw: U256, decimals: u32) -> Result<Decimal> {
+    let value = Decimal::from_str(&raw.to_string())
+        .map_err(|e| ArgusError::CalculationError(format!("Failed to convert amount: {e}")))?;
+    Ok(value / Decimal::from(10u64.pow(decimals)))
+}
+
 pub fn format_address(address: &str) -> Result<String> {
     if !address.starts_with("0x") || address.len() != 42 {
         return Err(ArgusError::ConfigError(format!(